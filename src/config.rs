@@ -8,11 +8,17 @@ use std::path::PathBuf;
 #[derive(Deserialize, Serialize)]
 pub struct Config {
     pub last_open: Option<String>,
+    /// Path of an iCalendar or YAML file listing the holidays that
+    /// business-day schedules skip
+    pub holidays: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Config { last_open: None }
+        Config {
+            last_open: None,
+            holidays: None,
+        }
     }
 }
 
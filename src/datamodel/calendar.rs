@@ -0,0 +1,75 @@
+//! Calendar arithmetic that does not panic on dates that do not exist
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::convert::TryFrom;
+
+/// Returns the number of days in the month, or 0 for an invalid month
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+pub fn days_in_year(year: i32) -> u32 {
+    match is_leap_year(year) {
+        true => 366,
+        false => 365,
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+/// Returns the year and month `n` months after the given month
+pub fn add_months(year: i32, month: u32, n: i64) -> Option<(i32, u32)> {
+    let month_idx = year as i64 * 12 + month as i64 - 1 + n;
+    let year = i32::try_from(month_idx.div_euclid(12)).ok()?;
+    Some((year, month_idx.rem_euclid(12) as u32 + 1))
+}
+
+/// Returns the date, or the last day of the month if the month is shorter than
+/// `day`. Returns None for an invalid month or day 0.
+pub fn clamped_date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day.min(days_in_month(year, month)))
+}
+
+/// Returns the nth weekday of the month, counting from the end of the month if
+/// `nth` is negative. Returns None if the month does not have such a day.
+pub fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    weekday: Weekday,
+    nth: i32,
+) -> Option<NaiveDate> {
+    let weeks = |n: i32| Duration::weeks(n as i64 - 1);
+    let date = match nth {
+        0 => return None,
+        nth if nth > 0 => {
+            let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+            let days_ahead =
+                (weekday.num_days_from_monday() + 7 - first.weekday().num_days_from_monday()) % 7;
+            first.checked_add_signed(Duration::days(days_ahead as i64) + weeks(nth))?
+        }
+        nth => {
+            let last = NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?;
+            let days_back =
+                (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+            last.checked_sub_signed(Duration::days(days_back as i64) + weeks(-nth))?
+        }
+    };
+
+    match date.month() == month {
+        true => Some(date),
+        false => None,
+    }
+}
+
+/// Returns the Monday of the week of the date
+pub fn week_start(date: &NaiveDate) -> NaiveDate {
+    *date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
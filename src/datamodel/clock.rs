@@ -0,0 +1,44 @@
+use crate::prelude::*;
+use chrono::{Duration, Local};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Source of the current time for scheduling. Everything that needs to know
+/// what time it is asks a clock so that the tracker can be driven at any
+/// given instant.
+pub trait Clock {
+    fn now(&self) -> LocalTime;
+}
+
+/// Reads the time from the system
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> LocalTime {
+        Local::now()
+    }
+}
+
+/// A clock that only moves when it's told to. Clones share the same time, so
+/// a clone can be handed to a tracker and advanced from the outside.
+#[derive(Clone, Debug)]
+pub struct ManualClock(Rc<Cell<LocalTime>>);
+
+impl ManualClock {
+    pub fn new(time: LocalTime) -> ManualClock {
+        ManualClock(Rc::new(Cell::new(time)))
+    }
+    pub fn set(&self, time: LocalTime) {
+        self.0.set(time);
+    }
+    pub fn advance(&self, by: Duration) {
+        self.0.set(self.0.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> LocalTime {
+        self.0.get()
+    }
+}
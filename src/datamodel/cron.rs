@@ -0,0 +1,330 @@
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Upper bound for the amount of days that are searched for the next
+/// occurrence. Covers the longest gap between leap days, eg. "0 0 29 2 *".
+const MAX_DAYS: i64 = 366 * 8;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// A five-field crontab expression: minute, hour, day of month, month and day
+/// of week, eg. "30 7 * * MON-FRI".
+///
+/// Fields accept `*`, numbers, names for months and weekdays, lists
+/// (`1,15`), ranges (`1-5`) and steps (`*/15`, `8-18/2`). As in crontab, if
+/// both the day of month and the day of week are restricted, a day matching
+/// either one of them triggers. A field starting with `*`, eg. `*/2`, is not
+/// restricted in this sense, and a day has to match both of the fields.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct Cron {
+    source: String,
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days_of_month: Vec<u32>,
+    months: Vec<u32>,
+    /// Days of the week with Sunday as 0
+    days_of_week: Vec<u32>,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Represents a cron expression that could not be parsed. Parameters: reason.
+#[derive(Debug, Clone)]
+pub struct CronError(pub String);
+
+impl Cron {
+    /// Returns the first time matching the expression after `from`, or None if
+    /// there is no such time in the foreseeable future
+    pub fn next_after(&self, from: &NaiveDateTime) -> Option<NaiveDateTime> {
+        // Cron has minute precision, start from the next full minute
+        let from = from.date().and_hms(from.hour(), from.minute(), 0) + Duration::minutes(1);
+
+        let mut date = from.date();
+        for _ in 0..MAX_DAYS {
+            if self.matches_date(&date) {
+                for &hour in &self.hours {
+                    for &minute in &self.minutes {
+                        let instance = date.and_hms(hour, minute, 0);
+                        if instance >= from {
+                            return Some(instance);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    /// Returns an estimate of the average time between occurrences
+    pub fn to_duration_heuristic(&self) -> Duration {
+        let month_fraction = self.months.len() as f64 / 12.;
+        let dom_fraction = self.days_of_month.len() as f64 / 30.44;
+        let dow_fraction = self.days_of_week.len() as f64 / 7.;
+        let day_fraction = match self.any_day_of_month || self.any_day_of_week {
+            true => dom_fraction.min(1.) * dow_fraction,
+            false => (dom_fraction + dow_fraction).min(1.),
+        };
+        let per_year = 365.
+            * month_fraction
+            * day_fraction
+            * self.hours.len() as f64
+            * self.minutes.len() as f64;
+
+        Duration::minutes((365. * 24. * 60. / per_year) as i64)
+    }
+
+    fn matches_date(&self, date: &chrono::NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(&date.day());
+        let dow = self
+            .days_of_week
+            .contains(&date.weekday().num_days_from_sunday());
+        match self.any_day_of_month || self.any_day_of_week {
+            true => dom && dow,
+            false => dom || dow,
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = CronError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() != 5 {
+            return Err(CronError(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+                fields.len()
+            )));
+        }
+
+        let minutes = parse_field("minute", fields[0], 0, 59, &[])?;
+        let hours = parse_field("hour", fields[1], 0, 23, &[])?;
+        let days_of_month = parse_field("day-of-month", fields[2], 1, 31, &[])?;
+        let months = parse_field("month", fields[3], 1, 12, MONTH_NAMES)?;
+        // Both 0 and 7 stand for Sunday
+        let mut days_of_week = parse_field("day-of-week", fields[4], 0, 7, WEEKDAY_NAMES)?
+            .into_iter()
+            .map(|d| d % 7)
+            .collect::<Vec<u32>>();
+        days_of_week.sort();
+        days_of_week.dedup();
+
+        Ok(Cron {
+            source: fields.join(" "),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            any_day_of_month: is_unrestricted(fields[2]),
+            any_day_of_week: is_unrestricted(fields[4]),
+        })
+    }
+}
+
+impl TryFrom<String> for Cron {
+    type Error = CronError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Cron> for String {
+    fn from(cron: Cron) -> String {
+        cron.source
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl error::Error for CronError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid cron expression: {}", self.0)
+    }
+}
+
+/// Returns true if the field starts from any value, eg. `*` or `*/2`, in which
+/// case it doesn't widen the days matched by the other day field
+fn is_unrestricted(field: &str) -> bool {
+    field.starts_with('*')
+}
+
+/// Parses a single cron field into a sorted list of the values it matches.
+/// `names` maps names to values starting from `min`.
+fn parse_field(
+    field_name: &str,
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<Vec<u32>, CronError> {
+    let parse_value = |s: &str| -> Result<u32, CronError> {
+        let upper = s.to_uppercase();
+        if let Some(idx) = names.iter().position(|&n| n == upper) {
+            return Ok(min + idx as u32);
+        }
+        match s.parse::<u32>() {
+            Ok(n) if n >= min && n <= max => Ok(n),
+            _ => Err(CronError(format!(
+                "{} value '{}' is not between {} and {}",
+                field_name, s, min, max
+            ))),
+        }
+    };
+
+    let mut values = vec![];
+    for item in field.split(',') {
+        let mut range_step = item.splitn(2, '/');
+        let range = range_step.next().unwrap_or("");
+        let step = match range_step.next() {
+            None => 1,
+            Some(step) => match step.parse::<u32>() {
+                Ok(n) if n > 0 => n,
+                _ => {
+                    return Err(CronError(format!(
+                        "{} step '{}' is not a positive number",
+                        field_name, step
+                    )))
+                }
+            },
+        };
+
+        let (first, last) = if range == "*" {
+            (min, max)
+        } else if let Some(dash) = range.find('-') {
+            let (first, last) = (
+                parse_value(&range[..dash])?,
+                parse_value(&range[dash + 1..])?,
+            );
+            if first > last {
+                return Err(CronError(format!(
+                    "{} range '{}' is reversed",
+                    field_name, range
+                )));
+            }
+            (first, last)
+        } else {
+            let value = parse_value(range)?;
+            // A single value with a step means "from value to max"
+            match item.contains('/') {
+                true => (value, max),
+                false => (value, value),
+            }
+        };
+        values.extend((first..=last).step_by(step as usize));
+    }
+    values.sort();
+    values.dedup();
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn weekday_mornings() {
+        let cron = "30 7 * * MON-FRI".parse::<Cron>().unwrap();
+        // 2020-01-03 is a Friday
+        assert_eq!(
+            cron.next_after(&dt("2020-01-03 07:29")),
+            Some(dt("2020-01-03 07:30"))
+        );
+        assert_eq!(
+            cron.next_after(&dt("2020-01-03 07:30")),
+            Some(dt("2020-01-06 07:30"))
+        );
+    }
+
+    #[test]
+    fn steps_and_lists() {
+        let cron = "*/20 8-10/2 1,15 * *".parse::<Cron>().unwrap();
+        assert_eq!(
+            cron.next_after(&dt("2020-01-01 08:41")),
+            Some(dt("2020-01-01 10:00"))
+        );
+        assert_eq!(
+            cron.next_after(&dt("2020-01-01 10:41")),
+            Some(dt("2020-01-15 08:00"))
+        );
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week() {
+        // Either the 13th or any Friday
+        let cron = "0 12 13 * 5".parse::<Cron>().unwrap();
+        // 2020-01-10 is a Friday
+        assert_eq!(
+            cron.next_after(&dt("2020-01-09 00:00")),
+            Some(dt("2020-01-10 12:00"))
+        );
+        assert_eq!(
+            cron.next_after(&dt("2020-01-11 00:00")),
+            Some(dt("2020-01-13 12:00"))
+        );
+    }
+
+    #[test]
+    fn stepped_day_of_month_is_not_widened_by_any_day_of_week() {
+        // Every other day, not every day
+        let cron = "0 12 */2 * *".parse::<Cron>().unwrap();
+        assert_eq!(
+            cron.next_after(&dt("2020-01-01 13:00")),
+            Some(dt("2020-01-03 12:00"))
+        );
+        // Every other day that is also a Friday, like crontab does
+        let cron = "0 12 */2 * 5".parse::<Cron>().unwrap();
+        // 2020-01-03 is a Friday, 2020-01-10 is an even day
+        assert_eq!(
+            cron.next_after(&dt("2020-01-04 00:00")),
+            Some(dt("2020-01-17 12:00"))
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expr in &[
+            "",
+            "* * * *",
+            "* * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "* * * FOO *",
+        ] {
+            assert!(expr.parse::<Cron>().is_err(), "'{}' was accepted", expr);
+        }
+    }
+}
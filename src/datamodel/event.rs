@@ -2,6 +2,8 @@ mod status;
 
 use crate::datamodel::*;
 use crate::prelude::*;
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 pub use status::*;
 
@@ -10,6 +12,25 @@ pub struct EventData {
     text: String,
     interval: Interval,
     stacks: bool,
+    /// IANA time zone in which periodic times are interpreted, the local time
+    /// zone is used if not set
+    timezone: Option<Tz>,
+    /// Date on which the event triggers for the first time at the earliest, in
+    /// the event's time zone
+    starts: Option<NaiveDate>,
+    /// Last date on which the event may trigger, in the event's time zone
+    ends: Option<NaiveDate>,
+    /// Number of times the event triggers before it is finished
+    max_occurrences: Option<u32>,
+    /// How long before triggering an upcoming occurrence may be completed
+    early_window: Option<TimeDelta>,
+    /// How long a triggered occurrence may stay pending before the event is
+    /// overdue, the length of the interval is used if not set
+    grace_period: Option<TimeDelta>,
+    /// Yearly ranges of days outside of which the event doesn't trigger, the
+    /// event is active all year if empty
+    #[serde(default)]
+    seasons: Vec<Season>,
 }
 
 impl EventData {
@@ -18,8 +39,57 @@ impl EventData {
             interval,
             text,
             stacks: false,
+            timezone: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            early_window: None,
+            grace_period: None,
+            seasons: vec![],
         }
     }
+    pub fn with_timezone(self, timezone: Tz) -> EventData {
+        EventData {
+            timezone: Some(timezone),
+            ..self
+        }
+    }
+    pub fn with_stacks(self, stacks: bool) -> EventData {
+        EventData { stacks, ..self }
+    }
+    pub fn with_start(self, starts: NaiveDate) -> EventData {
+        EventData {
+            starts: Some(starts),
+            ..self
+        }
+    }
+    pub fn with_end(self, ends: NaiveDate) -> EventData {
+        EventData {
+            ends: Some(ends),
+            ..self
+        }
+    }
+    pub fn with_max_occurrences(self, max_occurrences: u32) -> EventData {
+        EventData {
+            max_occurrences: Some(max_occurrences),
+            ..self
+        }
+    }
+    pub fn with_early_window(self, early_window: TimeDelta) -> EventData {
+        EventData {
+            early_window: Some(early_window),
+            ..self
+        }
+    }
+    pub fn with_grace_period(self, grace_period: TimeDelta) -> EventData {
+        EventData {
+            grace_period: Some(grace_period),
+            ..self
+        }
+    }
+    pub fn with_seasons(self, seasons: Vec<Season>) -> EventData {
+        EventData { seasons, ..self }
+    }
     pub fn text(&self) -> &str {
         &self.text
     }
@@ -29,6 +99,59 @@ impl EventData {
     pub fn stacks(&self) -> bool {
         self.stacks
     }
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone
+    }
+    pub fn starts(&self) -> Option<NaiveDate> {
+        self.starts
+    }
+    pub fn ends(&self) -> Option<NaiveDate> {
+        self.ends
+    }
+    pub fn max_occurrences(&self) -> Option<u32> {
+        self.max_occurrences
+    }
+    pub fn early_window(&self) -> Option<&TimeDelta> {
+        self.early_window.as_ref()
+    }
+    pub fn grace_period(&self) -> Option<&TimeDelta> {
+        self.grace_period.as_ref()
+    }
+    pub fn seasons(&self) -> &[Season] {
+        &self.seasons
+    }
+    /// Returns true if the event is active on the date by its seasons
+    pub fn is_in_season(&self, date: NaiveDate) -> bool {
+        self.seasons.is_empty() || self.seasons.iter().any(|season| season.contains(date))
+    }
+    /// Returns the first day of the next season after `date`, or None if the
+    /// event is active all year
+    pub fn next_season_start(&self, date: NaiveDate) -> Option<NaiveDate> {
+        self.seasons
+            .iter()
+            .map(|season| season.next_start_after(date))
+            .min()
+    }
+    /// Returns true if the event has a start, an end or an occurrence limit
+    pub fn is_bounded(&self) -> bool {
+        self.starts.is_some() || self.ends.is_some() || self.max_occurrences.is_some()
+    }
+    /// Returns the wall-clock time of `time` in the event's time zone
+    pub fn wall_clock(&self, time: &LocalTime) -> NaiveDateTime {
+        match self.timezone {
+            Some(tz) => time.with_timezone(&tz).naive_local(),
+            None => time.naive_local(),
+        }
+    }
+    /// Returns the instant of the wall-clock time `naive` in the event's time
+    /// zone, see `resolve_local` for how daylight saving transitions are
+    /// handled
+    pub fn instant(&self, naive: &NaiveDateTime) -> LocalTime {
+        match self.timezone {
+            Some(tz) => resolve_local(&tz, naive).with_timezone(&Local),
+            None => resolve_local(&Local, naive),
+        }
+    }
 }
 
 impl std::fmt::Display for EventData {
@@ -37,10 +160,40 @@ impl std::fmt::Display for EventData {
             false => " (re-trigger overrides)",
             true => " (re-trigger stacks)",
         };
+        let tz_str = match self.timezone {
+            Some(tz) => format!(" in {}", tz.name()),
+            None => String::new(),
+        };
+        let mut bounds_str = String::new();
+        if let Some(starts) = self.starts {
+            bounds_str += &format!(" from {}", starts.format("%-d.%-m.%Y"));
+        }
+        if let Some(ends) = self.ends {
+            bounds_str += &format!(" until {}", ends.format("%-d.%-m.%Y"));
+        }
+        if let Some(n) = self.max_occurrences {
+            bounds_str += &format!(" at most {} times", n);
+        }
+        let early_str = match &self.early_window {
+            Some(window) => format!(" (completable {} early)", window),
+            None => String::new(),
+        };
+        if !self.seasons.is_empty() {
+            let seasons = self
+                .seasons
+                .iter()
+                .map(|season| season.to_string())
+                .collect::<Vec<String>>();
+            bounds_str += &format!(" in season {}", seasons.join(", "));
+        }
+        let grace_str = match &self.grace_period {
+            Some(grace) => format!(" (overdue after {})", grace),
+            None => String::new(),
+        };
         write!(
             f,
-            "EventData {{ \"{}\", interval: {}{} }}",
-            self.text, &self.interval, stack_str
+            "EventData {{ \"{}\", interval: {}{}{}{}{}{} }}",
+            self.text, &self.interval, tz_str, bounds_str, stack_str, early_str, grace_str
         )
     }
 }
@@ -1,10 +1,73 @@
 use super::*;
-use std::default::Default;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(from = "StoredStatus")]
 pub struct Status {
     trigger_times: Vec<Time>,
     pub status: StatusKind,
+    /// Number of times the event has triggered
+    #[serde(default)]
+    occurrences: u32,
+    /// Everything that has happened to the event, oldest first
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    /// Number of upcoming occurrences to skip
+    #[serde(default)]
+    skips: u32,
+    /// Time of the latest skipped occurrence, the schedule counts on from it
+    #[serde(default)]
+    skipped: Option<Time>,
+    /// Time since which the event has been paused, paused events don't
+    /// trigger
+    #[serde(default)]
+    paused: Option<Time>,
+    /// Occurrences before this time don't trigger, set at the end of a pause
+    /// or a vacation
+    #[serde(default)]
+    resumed: Option<Time>,
+    /// Time of registration, recurrence rules without a start time are anchored
+    /// to it
+    #[serde(default)]
+    registered: Option<Time>,
+}
+
+/// The stored form of `Status`. Files from before the upcoming skips were
+/// counted have no `skips`, and mark a pending skip with a `Skip` status
+/// instead.
+#[derive(Deserialize)]
+struct StoredStatus {
+    trigger_times: Vec<Time>,
+    status: StatusKind,
+    #[serde(default)]
+    occurrences: u32,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+    skips: Option<u32>,
+    #[serde(default)]
+    skipped: Option<Time>,
+    #[serde(default)]
+    paused: Option<Time>,
+    #[serde(default)]
+    resumed: Option<Time>,
+    #[serde(default)]
+    registered: Option<Time>,
+}
+
+/// A record of an action on an event at a certain time
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub time: Time,
+    pub action: HistoryAction,
+    /// The upcoming occurrence that was completed ahead of time, if any
+    #[serde(default)]
+    pub occurrence: Option<Time>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryAction {
+    Trigger,
+    Complete,
+    Skip,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -15,8 +78,14 @@ pub enum StatusKind {
     Triggered,
     // Completed and ready to trigger again with time of completion
     Completed(Time),
-    // Skipped until next trigger, with time of skip
+    // The last occurrence was skipped and the event waits for the next one, with time of the
+    // skipped occurrence
     Skip(Time),
+    // Past its end date or occurrence limit and never triggering again, with time of
+    // finishing
+    Finished(Time),
+    // Triggered but postponed, triggers again at the given time
+    Snoozed(Time),
 }
 
 /// Implements PartialEq such that Dormant(t), Completed(t) and Skip(t)
@@ -29,87 +98,188 @@ impl PartialEq for StatusKind {
             (Completed(_), Completed(_)) => true,
             (Skip(_), Skip(_)) => true,
             (Triggered, Triggered) => true,
+            (Finished(_), Finished(_)) => true,
+            (Snoozed(_), Snoozed(_)) => true,
             // Cover the false cases to future-proof and cause a compile error when a new variant is
             // added.
             (Dormant(_), _) => false,
             (Triggered, _) => false,
             (Completed(_), _) => false,
             (Skip(_), _) => false,
+            (Finished(_), _) => false,
+            (Snoozed(_), _) => false,
         }
     }
 }
 
-impl Default for Status {
-    fn default() -> Self {
-        Status {
-            trigger_times: vec![],
-            status: StatusKind::Dormant(Time::now()),
+impl std::fmt::Display for HistoryAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryAction::Trigger => write!(f, "triggered"),
+            HistoryAction::Complete => write!(f, "completed"),
+            HistoryAction::Skip => write!(f, "skipped"),
         }
     }
 }
 
-impl Default for StatusKind {
-    fn default() -> Self {
-        StatusKind::Dormant(Time::now())
+impl From<StoredStatus> for Status {
+    fn from(stored: StoredStatus) -> Status {
+        let mut status = Status {
+            trigger_times: stored.trigger_times,
+            status: stored.status,
+            occurrences: stored.occurrences,
+            history: stored.history,
+            skips: stored.skips.unwrap_or(0),
+            skipped: stored.skipped,
+            paused: stored.paused,
+            resumed: stored.resumed,
+            registered: stored.registered,
+        };
+        // A legacy skip is still pending, it's recorded once the skipped occurrence passes
+        if let (None, StatusKind::Skip(skipped)) = (stored.skips, &status.status) {
+            let skipped = skipped.0;
+            status.skips = 1;
+            if let Some(last) = status.history.last() {
+                if last.action == HistoryAction::Skip && last.time.0 == skipped {
+                    status.history.pop();
+                }
+            }
+        }
+        status
     }
 }
 
 impl Status {
-    /// Use `.default()` to construct a status with the correct internal state.
-    /// This secondary
-    // constructor is intended for use in testing.
-    #[cfg(test)]
+    /// Returns a status for an event registered at `time`
     pub fn from_time(time: Time) -> Status {
         Status {
             trigger_times: vec![],
-            status: StatusKind::Dormant(time),
+            status: StatusKind::Dormant(time.clone()),
+            occurrences: 0,
+            history: vec![],
+            skips: 0,
+            skipped: None,
+            paused: None,
+            resumed: None,
+            registered: Some(time),
         }
     }
 
     /// Returns true if status is exactly `Triggered`
     pub fn is_triggered(&self) -> bool {
         match self.status {
-            StatusKind::Dormant(_) | StatusKind::Completed(_) | StatusKind::Skip(_) => false,
+            StatusKind::Dormant(_)
+            | StatusKind::Completed(_)
+            | StatusKind::Skip(_)
+            | StatusKind::Finished(_)
+            | StatusKind::Snoozed(_) => false,
             StatusKind::Triggered => true,
         }
     }
     /// Returns true if status is exactly `Completed`
     pub fn is_done(&self) -> bool {
         match self.status {
-            StatusKind::Dormant { .. } | StatusKind::Triggered { .. } | StatusKind::Skip { .. } => {
-                false
-            }
+            StatusKind::Dormant { .. }
+            | StatusKind::Triggered { .. }
+            | StatusKind::Skip { .. }
+            | StatusKind::Finished { .. }
+            | StatusKind::Snoozed { .. } => false,
             StatusKind::Completed { .. } => true,
         }
     }
+    /// Returns true if status is exactly `Snoozed`
+    pub fn is_snoozed(&self) -> bool {
+        matches!(self.status, StatusKind::Snoozed(_))
+    }
+    /// Returns true if status is exactly `Finished`
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, StatusKind::Finished(_))
+    }
+    /// Returns true if the event is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+    /// Returns the time before which occurrences don't trigger
+    pub fn resumed(&self) -> Option<LocalTime> {
+        self.resumed.as_ref().map(|t| t.0)
+    }
+    /// Returns the time the event was registered. Files from before the time of
+    /// registration was kept fall back to the earliest time on record.
+    pub fn registered(&self) -> Option<LocalTime> {
+        match (&self.registered, &self.status) {
+            (Some(t), _) | (None, StatusKind::Dormant(t)) => Some(t.0),
+            _ => self.history.first().map(|e| e.time.0),
+        }
+    }
+    /// Returns the number of times the event has triggered, skipped
+    /// occurrences are not counted
+    pub fn occurrences(&self) -> u32 {
+        self.occurrences
+    }
+    /// Returns the number of upcoming occurrences that will be skipped
+    pub fn skips(&self) -> u32 {
+        self.skips
+    }
+    /// Returns the time of the latest skipped occurrence
+    pub fn skipped(&self) -> Option<LocalTime> {
+        self.skipped.as_ref().map(|t| t.0)
+    }
 
-    /// Returns true if the event moved from an untriggered start to a triggered
-    /// state
-    pub fn trigger_now(&mut self) -> bool {
-        let now = Time::now();
-        match &self.status {
-            StatusKind::Dormant { .. } | StatusKind::Completed(_) => {
+    /// Triggers the event at the given time. Returns true if the event moved
+    /// from an untriggered start to a triggered state. Finished events are not
+    /// triggered again, and triggering an event that is triggered already
+    /// doesn't count as a new occurrence.
+    pub fn trigger_at(&mut self, now: Time) -> bool {
+        self.trigger(now, false)
+    }
+    /// Triggers a scheduled occurrence of the event at its time, see
+    /// `trigger_at`. If occurrences are set to be skipped, the occurrence is
+    /// skipped instead of triggered. Every scheduled occurrence that isn't
+    /// skipped counts, including the ones that stack on a triggered event.
+    pub fn trigger_occurrence_at(&mut self, at: Time) -> bool {
+        self.trigger(at, true)
+    }
+    fn trigger(&mut self, now: Time, scheduled: bool) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+        // Skips apply to scheduled occurrences only, triggering by hand always triggers
+        if scheduled && self.skips > 0 {
+            self.skips -= 1;
+            self.record(HistoryAction::Skip, &now);
+            self.skipped = Some(now.clone());
+            // Pending occurrences of a triggered event are kept
+            if !self.is_triggered() && !self.is_snoozed() {
+                self.status = StatusKind::Skip(now);
+            }
+            return false;
+        }
+        let triggered = match &self.status {
+            StatusKind::Dormant(_) | StatusKind::Completed(_) | StatusKind::Skip(_) => {
+                self.record(HistoryAction::Trigger, &now);
                 self.status = StatusKind::Triggered;
                 self.trigger_times = vec![now];
                 true
             }
-            StatusKind::Skip(_time_of_skip) => {
-                self.status = StatusKind::Completed(now);
-                self.trigger_times = vec![];
-                false
-            }
-            // Just add another triggering for already triggered events
-            StatusKind::Triggered => {
+            // Just add another triggering for already triggered events, waking snoozed ones
+            StatusKind::Triggered | StatusKind::Snoozed(_) => {
+                self.status = StatusKind::Triggered;
+                self.record(HistoryAction::Trigger, &now);
                 self.trigger_times.push(now);
                 false
             }
+            StatusKind::Finished(_) => unreachable!(),
+        };
+        if triggered || scheduled {
+            self.occurrences += 1;
         }
+        triggered
     }
-    /// Returns true if the event was completed at this time as effect of this
-    /// function. Resets the list of trigger times to vec![]. Sets the item
-    /// completed even if the item is set to be skipped.
-    pub fn complete_now(&mut self) -> bool {
-        let now = Time::now();
+    /// Completes the event at the given time. Returns true if the event was
+    /// completed at this time as effect of this function. Resets the list of
+    /// trigger times to vec![]. Occurrences set to be skipped are still
+    /// skipped.
+    pub fn complete_at(&mut self, now: Time) -> bool {
         let ret;
         if let StatusKind::Completed(_) = self.status {
             ret = true;
@@ -117,21 +287,116 @@ impl Status {
             ret = false;
         }
 
+        // Each of the pending occurrences is completed
+        for _ in 0..std::cmp::max(self.trigger_times.len(), 1) {
+            self.record(HistoryAction::Complete, &now);
+        }
         self.trigger_times = vec![];
         self.status = StatusKind::Completed(now);
 
         ret
     }
-    /// Sets the event as skipped until the next trigger time
-    pub fn skip_now(&mut self) {
-        let now = Time::now();
-        *self = Status {
-            trigger_times: vec![],
-            status: StatusKind::Skip(now),
+    /// Completes the upcoming occurrence scheduled at `occurrence` ahead of
+    /// time. The completion is recorded with the occurrence it is for, and the
+    /// event waits for the one after it.
+    pub fn complete_early_at(&mut self, now: Time, occurrence: Time) {
+        self.occurrences += 1;
+        self.history.push(HistoryEntry {
+            time: now,
+            action: HistoryAction::Complete,
+            occurrence: Some(occurrence.clone()),
+        });
+        self.trigger_times = vec![];
+        self.status = StatusKind::Completed(occurrence);
+    }
+    /// Completes the earliest pending occurrence of a triggered event at the
+    /// given time. The event is completed when no occurrences remain.
+    pub fn complete_one_at(&mut self, now: Time) {
+        match self.status {
+            StatusKind::Triggered | StatusKind::Snoozed(_) if self.trigger_times.len() > 1 => {
+                self.record(HistoryAction::Complete, &now);
+                self.trigger_times.remove(0);
+            }
+            _ => {
+                self.complete_at(now);
+            }
+        }
+    }
+    /// Postpones a triggered event until the given time, the pending
+    /// occurrences are kept. Returns false if the event is not triggered.
+    pub fn snooze_until(&mut self, until: Time) -> bool {
+        match self.status {
+            StatusKind::Triggered | StatusKind::Snoozed(_) => {
+                self.status = StatusKind::Snoozed(until);
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Sets a snoozed event back to triggered
+    pub fn wake(&mut self) {
+        if let StatusKind::Snoozed(_) = self.status {
+            self.status = StatusKind::Triggered;
+        }
+    }
+    /// Sets the next `count` occurrences of the event to be skipped, the
+    /// current occurrences of a triggered event are not affected
+    pub fn skip_next(&mut self, count: u32) {
+        self.skips += count;
+    }
+    /// Pauses the event at the given time. Returns false if the event is
+    /// paused already.
+    pub fn pause_at(&mut self, now: Time) -> bool {
+        match self.paused {
+            Some(_) => false,
+            None => {
+                self.paused = Some(now);
+                true
+            }
+        }
+    }
+    /// Resumes a paused event at the given time, the occurrences during the
+    /// pause don't trigger. Returns false if the event is not paused.
+    pub fn resume_at(&mut self, now: Time) -> bool {
+        match self.paused.take() {
+            Some(_) => {
+                self.schedule_from(now);
+                true
+            }
+            None => false,
+        }
+    }
+    /// Sets the event to not trigger occurrences before the given time
+    pub fn schedule_from(&mut self, time: Time) {
+        self.resumed = match self.resumed.take() {
+            Some(resumed) if resumed.0 > time.0 => Some(resumed),
+            _ => Some(time),
         };
     }
+    /// Sets the event as finished at the given time, it will not trigger again
+    pub fn finish_at(&mut self, now: Time) {
+        self.trigger_times = vec![];
+        self.skips = 0;
+        self.status = StatusKind::Finished(now);
+    }
 
+    /// Returns the times of the occurrences that have triggered since the
+    /// event was last completed
+    pub fn trigger_times(&self) -> &[Time] {
+        &self.trigger_times
+    }
     pub fn prev_trigger_time(&self) -> Option<LocalTime> {
         self.trigger_times.last().map(|t| t.0)
     }
+    /// Returns the history of the event, oldest first
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+    fn record(&mut self, action: HistoryAction, time: &Time) {
+        self.history.push(HistoryEntry {
+            time: time.clone(),
+            action,
+            occurrence: None,
+        });
+    }
 }
@@ -0,0 +1,188 @@
+//! Business days and the holiday calendar that business-day schedules skip
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A set of holidays on which business-day schedules don't trigger, in
+/// addition to weekends. The tracker holds the calendar and hands it to the
+/// schedules, see `Tracker::with_holidays`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HolidayCalendar {
+    dates: BTreeSet<NaiveDate>,
+}
+
+/// Represents a holiday calendar that could not be loaded. Parameters: reason.
+#[derive(Debug, Clone)]
+pub struct HolidayError(pub String);
+
+impl HolidayCalendar {
+    pub fn new(dates: impl IntoIterator<Item = NaiveDate>) -> HolidayCalendar {
+        HolidayCalendar {
+            dates: dates.into_iter().collect(),
+        }
+    }
+    /// Loads the holidays from an iCalendar file, if the contents are in the
+    /// iCalendar format, or otherwise from a YAML list of dates, eg.
+    /// "- 2020-12-24"
+    pub fn from_file<P>(path: P) -> Result<HolidayCalendar, HolidayError>
+    where
+        P: AsRef<Path>,
+    {
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            HolidayError(format!(
+                "cannot read '{}': {}",
+                path.as_ref().to_string_lossy(),
+                e
+            ))
+        })?;
+        match contents.trim_start().starts_with("BEGIN:VCALENDAR") {
+            true => HolidayCalendar::from_ics(&contents),
+            false => HolidayCalendar::from_yaml(&contents),
+        }
+    }
+    /// Parses a YAML list of dates
+    pub fn from_yaml(s: &str) -> Result<HolidayCalendar, HolidayError> {
+        serde_yaml::from_str::<Vec<NaiveDate>>(s)
+            .map(HolidayCalendar::new)
+            .map_err(|e| HolidayError(e.to_string()))
+    }
+    /// Parses the days of the events in an iCalendar file. An event lasts
+    /// until its end date, exclusive, or a single day if it has none.
+    /// Recurring events are not expanded.
+    pub fn from_ics(s: &str) -> Result<HolidayCalendar, HolidayError> {
+        // Dates are given as "DTSTART;VALUE=DATE:20201224" or "DTSTART:20201224T000000Z"
+        let parse_date = |line: &str| {
+            let value = line.split_once(':').map_or("", |(_, value)| value).trim();
+            NaiveDate::parse_from_str(value.get(..8).unwrap_or(value), "%Y%m%d")
+                .map_err(|_| HolidayError(format!("cannot parse a date from '{}'", line)))
+        };
+
+        let mut dates = BTreeSet::new();
+        let (mut starts, mut ends) = (None, None);
+        for line in s.lines().map(|line| line.trim()) {
+            let name = line.split([';', ':']).next().unwrap_or("");
+            match name {
+                "BEGIN" if line == "BEGIN:VEVENT" => {
+                    starts = None;
+                    ends = None;
+                }
+                "DTSTART" => starts = Some(parse_date(line)?),
+                "DTEND" => ends = Some(parse_date(line)?),
+                "END" if line == "END:VEVENT" => {
+                    let starts = starts
+                        .ok_or_else(|| HolidayError("an event has no start date".to_string()))?;
+                    let ends = std::cmp::max(ends.unwrap_or_else(|| starts.succ()), starts.succ());
+                    let mut date = starts;
+                    while date < ends {
+                        dates.insert(date);
+                        date = date.succ();
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(HolidayCalendar { dates })
+    }
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.dates.contains(&date)
+    }
+    /// Returns true if the date is a weekday that is not a holiday
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+    /// Returns the date if it's a business day, or the first business day
+    /// after it
+    pub fn business_day_from(&self, date: NaiveDate) -> NaiveDate {
+        // Terminates as there are finitely many holidays
+        let mut date = date;
+        while !self.is_business_day(date) {
+            date = date.succ();
+        }
+        date
+    }
+    /// Returns the date `n` business days after the date
+    pub fn add_business_days(&self, date: NaiveDate, n: u32) -> NaiveDate {
+        (0..n).fold(date, |date, _| {
+            self.business_day_from(date + Duration::days(1))
+        })
+    }
+}
+
+impl std::error::Error for HolidayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl fmt::Display for HolidayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot load holidays: {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd(y, m, d)
+    }
+
+    #[test]
+    fn holidays_parse_from_ics_and_yaml() {
+        let ics = "BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VEVENT
+SUMMARY:Christmas
+DTSTART;VALUE=DATE:20201224
+DTEND;VALUE=DATE:20201227
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:New Year's Day
+DTSTART:20210101T000000Z
+END:VEVENT
+END:VCALENDAR
+";
+        let expected = HolidayCalendar::new(vec![
+            ymd(2020, 12, 24),
+            ymd(2020, 12, 25),
+            ymd(2020, 12, 26),
+            ymd(2021, 1, 1),
+        ]);
+        assert_eq!(HolidayCalendar::from_ics(ics).unwrap(), expected);
+
+        let yaml = "---
+- 2020-12-24
+- 2020-12-25
+- 2020-12-26
+- 2021-01-01
+";
+        assert_eq!(HolidayCalendar::from_yaml(yaml).unwrap(), expected);
+    }
+
+    #[test]
+    fn business_days_skip_weekends_and_holidays() {
+        let calendar = HolidayCalendar::new(vec![ymd(2020, 12, 24), ymd(2020, 12, 25)]);
+        // Wednesday 23rd + 1 business day skips the holidays and the weekend
+        assert_eq!(
+            calendar.add_business_days(ymd(2020, 12, 23), 1),
+            ymd(2020, 12, 28)
+        );
+        assert_eq!(
+            calendar.add_business_days(ymd(2020, 12, 21), 3),
+            ymd(2020, 12, 28)
+        );
+        assert_eq!(
+            calendar.business_day_from(ymd(2020, 12, 26)),
+            ymd(2020, 12, 28)
+        );
+        assert_eq!(
+            calendar.add_business_days(ymd(2020, 12, 26), 0),
+            ymd(2020, 12, 26)
+        );
+    }
+}
@@ -1,5 +1,9 @@
+use crate::datamodel::calendar::{
+    add_months, clamped_date, days_in_month, nth_weekday_of_month, week_start,
+};
+use crate::datamodel::{Cron, EventData, HolidayCalendar, RRule};
 use crate::prelude::*;
-use chrono::{Duration, NaiveTime, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -9,21 +13,43 @@ pub enum Interval {
     /// A fixed interval between a certain time on multiple subsequent specified
     /// days
     Periodic(TimePeriod),
+    /// A recurrence rule in the iCalendar (RFC 5545) RRULE format
+    RRule(RRule),
+    /// A five-field crontab expression
+    Cron(Cron),
+    /// Triggers only once, at the given wall-clock time
+    Once(NaiveDateTime),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum TimeDelta {
     Days(i64),
     Hm(i64, i64),
+    /// Weekdays that are not holidays, see `HolidayCalendar`
+    BusinessDays(u32),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum TimePeriod {
     Annual(AnnualDay, NaiveTime),
     Monthly(MonthlyDay, NaiveTime),
+    /// Triggers monthly on the nth or nth-to-last weekday of the month, eg. the
+    /// first Monday
+    MonthlyWeekday(NthWeekday, NaiveTime),
+    /// Triggers monthly on the last day of the month
+    MonthEnd(NaiveTime),
     Weekly(Weekday, NaiveTime),
+    /// Triggers on each of the given weekdays on every nth week
+    MultiWeekly(WeeklyDays, NaiveTime),
     Daily(NaiveTime),
-    MultiAnnual(Vec<AnnualDay>), // Not implemented
+    /// Triggers on weekdays that are not holidays, see `HolidayCalendar`
+    BusinessDaily(NaiveTime),
+    /// Triggers daily at each of the given times
+    MultiDaily(Vec<NaiveTime>),
+    /// Triggers several times a day at a fixed stride within an active window
+    SubDaily(DailyWindow),
+    /// Triggers annually on each of the given days, each at its own time
+    MultiAnnual(Vec<(AnnualDay, NaiveTime)>),
 }
 
 impl Interval {
@@ -32,6 +58,22 @@ impl Interval {
         match self {
             FromLastCompletion(delta) => Some(delta.to_duration()),
             Periodic(period) => period.to_duration_heuristic(),
+            RRule(rule) => Some(rule.to_duration_heuristic()),
+            Cron(cron) => Some(cron.to_duration_heuristic()),
+            Once(_) => None,
+        }
+    }
+    /// Returns false if the interval triggers at most once
+    pub fn is_recurring(&self) -> bool {
+        !matches!(self, Interval::Once(_))
+    }
+    /// Returns true if the interval stops producing occurrences at some point,
+    /// eg. a recurrence rule with COUNT or UNTIL
+    pub fn has_end(&self) -> bool {
+        match self {
+            Interval::Once(_) => true,
+            Interval::RRule(rule) => rule.has_end(),
+            Interval::FromLastCompletion(_) | Interval::Periodic(_) | Interval::Cron(_) => false,
         }
     }
 }
@@ -41,11 +83,94 @@ impl TimePeriod {
         use TimePeriod::*;
         match self {
             Annual(_, _) => Some(Duration::days(365)),
-            Monthly(_, _) => Some(Duration::days(30)),
+            Monthly(_, _) | MonthlyWeekday(_, _) | MonthEnd(_) => Some(Duration::days(30)),
             Weekly(_, _) => Some(Duration::days(7)),
-            Daily(_) => Some(Duration::days(1)),
+            // Returns the average amount of time between the days
+            MultiWeekly(days, _) => match days.weekdays.len() {
+                0 => None,
+                n => Some(Duration::days(7) * days.every_n_weeks as i32 / n as i32),
+            },
+            Daily(_) | BusinessDaily(_) => Some(Duration::days(1)),
+            // Returns the average amount of time between the times
+            MultiDaily(times) => match times.len() {
+                0 => None,
+                n => Some(Duration::days(1) / n as i32),
+            },
+            SubDaily(window) => match window.every_minutes {
+                0 => None,
+                n => Some(Duration::minutes(n as i64)),
+            },
             // Returns the average amount of time between the days, which is 365 / number of days
-            MultiAnnual(d) => Some(Duration::days(365 / d.len() as i64)),
+            MultiAnnual(d) => match d.len() {
+                0 => None,
+                n => Some(Duration::days(365 / n as i64)),
+            },
+        }
+    }
+}
+
+impl TimePeriod {
+    /// Returns the first instance of the period after the wall-clock time
+    /// `prev`, or None if there is no such instance, eg. because the period
+    /// refers to a month that does not exist. Business days skip `holidays`.
+    pub fn next_after(
+        &self,
+        prev: &NaiveDateTime,
+        holidays: &HolidayCalendar,
+    ) -> Option<NaiveDateTime> {
+        use TimePeriod::*;
+        match self {
+            Annual(day, time) => day.next_after(prev, time),
+            // Pick the soonest of the annual instances
+            MultiAnnual(days) => days
+                .iter()
+                .filter_map(|(day, time)| day.next_after(prev, time))
+                .min(),
+            Monthly(day, time) => day.next_after(prev, time),
+            MonthlyWeekday(day, time) => day.next_after(prev, time),
+            MonthEnd(time) => (0..=1)
+                .filter_map(|n| add_months(prev.year(), prev.month(), n))
+                .filter_map(|(year, month)| clamped_date(year, month, 31))
+                .map(|date| date.and_time(*time))
+                .find(|instance| instance > prev),
+            Weekly(weekday, time) => {
+                let days_ahead = (weekday.num_days_from_monday() + 7
+                    - prev.weekday().num_days_from_monday())
+                    % 7;
+                let an_instance = prev
+                    .date()
+                    .checked_add_signed(Duration::days(days_ahead as i64))?
+                    .and_time(*time);
+
+                // If the constructed instance is not after our time, move it one week forward
+                match an_instance > *prev {
+                    true => Some(an_instance),
+                    false => an_instance.checked_add_signed(Duration::weeks(1)),
+                }
+            }
+            MultiWeekly(days, time) => days.next_after(prev, time),
+            Daily(time) => {
+                let an_instance = prev.date().and_time(*time);
+
+                // If the constructed instance is not after our time, move it one day forward
+                match an_instance > *prev {
+                    true => Some(an_instance),
+                    false => an_instance.checked_add_signed(Duration::days(1)),
+                }
+            }
+            BusinessDaily(time) => {
+                let date = match prev.date().and_time(*time) > *prev {
+                    true => prev.date(),
+                    false => prev.date().succ(),
+                };
+                Some(holidays.business_day_from(date).and_time(*time))
+            }
+            // Pick the soonest of the daily instances
+            MultiDaily(times) => times
+                .iter()
+                .filter_map(|time| Daily(*time).next_after(prev, holidays))
+                .min(),
+            SubDaily(window) => window.next_after(prev),
         }
     }
 }
@@ -56,6 +181,9 @@ impl std::fmt::Display for Interval {
         match self {
             FromLastCompletion(delta) => write!(f, "triggers {} after previous completion", delta),
             Periodic(p) => write!(f, "{}", p),
+            RRule(rule) => write!(f, "triggers by recurrence rule {}", rule),
+            Cron(cron) => write!(f, "triggers on cron schedule \"{}\"", cron),
+            Once(time) => write!(f, "triggers once on {}", time.format("%-d.%-m.%Y at %H:%M")),
         }
     }
 }
@@ -64,11 +192,16 @@ impl std::fmt::Display for TimePeriod {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use TimePeriod::*;
         match self {
-            MultiAnnual(days) => write!(
-                f,
-                "triggers multi-annually on following dates and times {:?}",
-                &days
-            ),
+            MultiAnnual(days) => {
+                write!(f, "triggers multi-annually on ")?;
+                for (idx, (day, time)) in days.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} at {}", day, time.format("%H:%M"))?;
+                }
+                Ok(())
+            }
             Annual(day, time) => write!(
                 f,
                 "triggers annually on {} at {}",
@@ -78,13 +211,41 @@ impl std::fmt::Display for TimePeriod {
             Monthly(day, time) => {
                 write!(f, "triggers monthly on {} at {}", day, time.format("%H:%M"))
             }
+            MonthlyWeekday(day, time) => write!(
+                f,
+                "triggers monthly on the {} at {}",
+                day,
+                time.format("%H:%M")
+            ),
+            MonthEnd(time) => write!(
+                f,
+                "triggers monthly on the last day at {}",
+                time.format("%H:%M")
+            ),
             Weekly(weekday, time) => write!(
                 f,
                 "triggers weekly on {} at {}",
                 weekday,
                 time.format("%H:%M")
             ),
+            MultiWeekly(days, time) => {
+                write!(f, "triggers {} at {}", days, time.format("%H:%M"))
+            }
             Daily(time) => write!(f, "triggers daily at {}", time.format("%H:%M")),
+            BusinessDaily(time) => {
+                write!(f, "triggers every business day at {}", time.format("%H:%M"))
+            }
+            MultiDaily(times) => {
+                write!(f, "triggers daily at ")?;
+                for (idx, time) in times.iter().enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", time.format("%H:%M"))?;
+                }
+                Ok(())
+            }
+            SubDaily(window) => write!(f, "triggers {}", window),
         }
     }
 }
@@ -94,6 +255,7 @@ impl std::fmt::Display for TimeDelta {
         use TimeDelta::*;
         match self {
             Days(n) => write!(f, "{} days", n),
+            BusinessDays(n) => write!(f, "{} business days", n),
             Hm(h, m) => {
                 match h {
                     0 => write!(f, ""),
@@ -108,30 +270,94 @@ impl std::fmt::Display for TimeDelta {
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Parses the format that `TimeDelta` is displayed in, eg. "3 days", "2d",
+/// "3 business days", "3bd", "1h30m" or "45m"
+impl std::str::FromStr for TimeDelta {
+    type Err = TimeDeltaError;
 
-    #[test]
-    fn hours_minutes_formats_right() {
-        let dt = TimeDelta::Hm(1, 20);
-        assert_eq!(&format!("{}", dt), "1h20m");
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let err = || TimeDeltaError(s.clone());
+        // Only positive deltas are accepted
+        for suffix in &[" business days", " business day", "bd"] {
+            if let Some(days) = s.strip_suffix(suffix) {
+                return match days.trim().parse() {
+                    Ok(days) if days > 0 => Ok(TimeDelta::BusinessDays(days)),
+                    _ => Err(err()),
+                };
+            }
+        }
+        for suffix in &[" days", " day", "days", "day", "d"] {
+            if let Some(days) = s.strip_suffix(suffix) {
+                return match days.trim().parse() {
+                    Ok(days) if days > 0 => Ok(TimeDelta::Days(days)),
+                    _ => Err(err()),
+                };
+            }
+        }
 
-        let dt = TimeDelta::Hm(0, 15);
-        assert_eq!(&format!("{}", dt), "15m");
+        let (hours, rest) = match s.find('h') {
+            Some(idx) => (s[..idx].parse().map_err(|_| err())?, &s[idx + 1..]),
+            None => (0, s.as_str()),
+        };
+        let minutes = match rest.strip_suffix('m') {
+            Some(minutes) => minutes.parse().map_err(|_| err())?,
+            None if rest.is_empty() && hours != 0 => 0,
+            None => return Err(err()),
+        };
+        match (hours, minutes) {
+            (h, m) if h < 0 || m < 0 || h + m == 0 => Err(err()),
+            (h, m) => Ok(TimeDelta::Hm(h, m)),
+        }
+    }
+}
 
-        let dt = TimeDelta::Hm(5, 0);
-        assert_eq!(&format!("{}", dt), "5h");
+/// Represents a time delta that could not be parsed. Parameters: input.
+#[derive(Debug, Clone)]
+pub struct TimeDeltaError(pub String);
+
+impl std::error::Error for TimeDeltaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl std::fmt::Display for TimeDeltaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid duration '{}', expected eg. \"2d\", \"3bd\", \"1h30m\" or \"45m\"",
+            self.0
+        )
     }
 }
 
 impl TimeDelta {
-    pub fn apply_to(&self, time: LocalTime) -> LocalTime {
-        time + self.to_duration()
+    /// Returns the time the delta after `time`. Business days are counted on
+    /// the wall clock of `event`'s time zone, skipping `holidays`, and keep
+    /// the time of day.
+    pub fn apply_to(
+        &self,
+        time: LocalTime,
+        event: &EventData,
+        holidays: &HolidayCalendar,
+    ) -> LocalTime {
+        match self {
+            TimeDelta::BusinessDays(n) => {
+                let wall_clock = event.wall_clock(&time);
+                let date = holidays.add_business_days(wall_clock.date(), *n);
+                event.instant(&date.and_time(wall_clock.time()))
+            }
+            _ => time + self.to_duration(),
+        }
     }
+    /// Returns the length of the delta, business days are approximated by
+    /// calendar days including weekends
     pub fn to_duration(&self) -> Duration {
         match self {
             TimeDelta::Days(d) => Duration::days(*d),
+            TimeDelta::BusinessDays(n) => Duration::days(*n as i64 * 7 / 5),
             TimeDelta::Hm(h, m) => Duration::hours(*h as i64) + Duration::minutes(*m as i64),
         }
     }
@@ -141,21 +367,445 @@ impl TimeDelta {
 pub struct AnnualDay {
     pub month: u32,
     pub day: u32,
+    /// What to do on years where the month does not have the day, eg. Feb.
+    /// 29th
+    #[serde(default)]
+    pub overflow: DayOverflow,
+}
+
+impl AnnualDay {
+    /// Returns the first instance of the day after `prev`
+    pub fn next_after(&self, prev: &NaiveDateTime, time: &NaiveTime) -> Option<NaiveDateTime> {
+        // A leap day that is skipped on common years can be up to 8 years away
+        (0..=8)
+            .filter_map(|n| self.overflow.resolve(prev.year() + n, self.month, self.day))
+            .map(|date| date.and_time(*time))
+            .find(|instance| instance > prev)
+    }
+    /// Returns true if the day is missing from the month on some years, ie. it
+    /// doesn't exist in a common year such as 2001
+    pub fn can_overflow(&self) -> bool {
+        self.day > days_in_month(2001, self.month)
+    }
 }
 
 impl std::fmt::Display for AnnualDay {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}.", self.month, self.day)
+        write!(f, "{}.{}.", self.month, self.day)?;
+        if self.can_overflow() {
+            write!(f, " ({})", self.overflow)?;
+        }
+        Ok(())
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct MonthlyDay {
     pub day: u32,
+    /// What to do on months that are shorter than the day
+    #[serde(default)]
+    pub overflow: DayOverflow,
+}
+
+impl MonthlyDay {
+    /// Returns the first instance of the day after `prev`
+    pub fn next_after(&self, prev: &NaiveDateTime, time: &NaiveTime) -> Option<NaiveDateTime> {
+        // Any day of the month exists at least once in three consecutive months
+        (0..=3)
+            .filter_map(|n| add_months(prev.year(), prev.month(), n))
+            .filter_map(|(year, month)| self.overflow.resolve(year, month, self.day))
+            .map(|date| date.and_time(*time))
+            .find(|instance| instance > prev)
+    }
+    /// Returns true if the day is missing from some months
+    pub fn can_overflow(&self) -> bool {
+        self.day > 28
+    }
 }
 
 impl std::fmt::Display for MonthlyDay {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.", self.day)
+        write!(f, "{}.", self.day)?;
+        if self.can_overflow() {
+            write!(f, " ({})", self.overflow)?;
+        }
+        Ok(())
+    }
+}
+
+/// A set of weekdays on every nth week, counting weeks from the week of the
+/// start date
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WeeklyDays {
+    pub weekdays: Vec<Weekday>,
+    pub every_n_weeks: u32,
+    /// No instances are produced before this date
+    pub start: NaiveDate,
+}
+
+impl WeeklyDays {
+    /// Returns the first instance of the weekdays after `prev`
+    pub fn next_after(&self, prev: &NaiveDateTime, time: &NaiveTime) -> Option<NaiveDateTime> {
+        if self.weekdays.is_empty() || self.every_n_weeks == 0 {
+            return None;
+        }
+        let start_week = week_start(&self.start);
+        let from = std::cmp::max(prev.date(), self.start);
+
+        // Every weekday of an active week is found within a stride and a week
+        (0..7 * (self.every_n_weeks as i64 + 1))
+            .filter_map(|n| from.checked_add_signed(Duration::days(n)))
+            .filter(|date| {
+                let weeks = week_start(date)
+                    .signed_duration_since(start_week)
+                    .num_weeks();
+                weeks % self.every_n_weeks as i64 == 0 && self.weekdays.contains(&date.weekday())
+            })
+            .map(|date| date.and_time(*time))
+            .find(|instance| instance > prev)
+    }
+}
+
+impl std::fmt::Display for WeeklyDays {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.every_n_weeks {
+            1 => write!(f, "weekly on ")?,
+            n => write!(f, "every {} weeks on ", n)?,
+        }
+        for (idx, weekday) in self.weekdays.iter().enumerate() {
+            if idx != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", weekday)?;
+        }
+        if self.every_n_weeks != 1 {
+            write!(f, " from {}", self.start.format("%-d.%-m.%Y"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Instances at a fixed stride within a daily window, eg. every 2 hours from
+/// 09:00 to 17:00 on weekdays. A window that ends before it starts continues
+/// past midnight.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DailyWindow {
+    pub every_minutes: u32,
+    /// Time of the first instance of the window
+    pub from: NaiveTime,
+    /// No instances are produced after this time, an instance exactly at it is
+    /// included
+    pub until: NaiveTime,
+    /// Days on which the window starts, every day if empty
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>,
+}
+
+impl DailyWindow {
+    /// Returns the first instance of the window after `prev`
+    pub fn next_after(&self, prev: &NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.every_minutes == 0 {
+            return None;
+        }
+        let stride = Duration::minutes(self.every_minutes as i64);
+        let mut length = self.until.signed_duration_since(self.from);
+        if length < Duration::zero() {
+            length = length + Duration::days(1);
+        }
+
+        // Start from the previous day in case its window is still open
+        (-1..=7)
+            .filter_map(|n| prev.date().checked_add_signed(Duration::days(n)))
+            .filter(|date| self.weekdays.is_empty() || self.weekdays.contains(&date.weekday()))
+            .find_map(|date| {
+                let start = date.and_time(self.from);
+                let end = start + length;
+                // Skip straight to the first instance after `prev`
+                let strides = match *prev < start {
+                    true => 0,
+                    false => {
+                        prev.signed_duration_since(start).num_minutes() / stride.num_minutes() + 1
+                    }
+                };
+                let instance = start + stride * strides as i32;
+                match instance <= end {
+                    true => Some(instance),
+                    false => None,
+                }
+            })
+    }
+}
+
+impl std::fmt::Display for DailyWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let every = TimeDelta::Hm(
+            self.every_minutes as i64 / 60,
+            self.every_minutes as i64 % 60,
+        );
+        write!(
+            f,
+            "every {} from {} to {}",
+            every,
+            self.from.format("%H:%M"),
+            self.until.format("%H:%M")
+        )?;
+        for (idx, weekday) in self.weekdays.iter().enumerate() {
+            match idx {
+                0 => write!(f, " on ")?,
+                _ => write!(f, ", ")?,
+            }
+            write!(f, "{}", weekday)?;
+        }
+        Ok(())
+    }
+}
+
+/// The nth weekday of a month, counting from the end of the month if `nth` is
+/// negative, eg. `NthWeekday { nth: -1, weekday: Weekday::Fri }` is the last
+/// Friday
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NthWeekday {
+    pub nth: i32,
+    pub weekday: Weekday,
+}
+
+impl NthWeekday {
+    /// Returns the first instance of the weekday after `prev`. Months that do
+    /// not have the weekday, eg. a fifth Monday, are skipped.
+    pub fn next_after(&self, prev: &NaiveDateTime, time: &NaiveTime) -> Option<NaiveDateTime> {
+        (0..=12)
+            .filter_map(|n| add_months(prev.year(), prev.month(), n))
+            .filter_map(|(year, month)| nth_weekday_of_month(year, month, self.weekday, self.nth))
+            .map(|date| date.and_time(*time))
+            .find(|instance| instance > prev)
+    }
+}
+
+impl std::fmt::Display for NthWeekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ordinal = |n: i32| match n {
+            1 => "first".to_string(),
+            2 => "second".to_string(),
+            3 => "third".to_string(),
+            4 => "fourth".to_string(),
+            5 => "fifth".to_string(),
+            n => format!("{}th", n),
+        };
+        match self.nth {
+            -1 => write!(f, "last {}", self.weekday),
+            n if n < 0 => write!(f, "{} to last {}", ordinal(-n), self.weekday),
+            n => write!(f, "{} {}", ordinal(n), self.weekday),
+        }
+    }
+}
+
+/// Policy for days that do not exist in every month or year, such as the 31st
+/// or Feb. 29th
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum DayOverflow {
+    /// Trigger on the last day of the month instead
+    #[default]
+    Clamp,
+    /// Trigger on the day after the last day of the month instead
+    Roll,
+    /// Do not trigger on that month or year
+    Skip,
+}
+
+impl DayOverflow {
+    /// Returns the date for the day of the month according to the policy, or
+    /// None if the day is skipped or invalid
+    pub fn resolve(self, year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        if day == 0 || day > 31 {
+            return None;
+        }
+        match self {
+            DayOverflow::Clamp => clamped_date(year, month, day),
+            DayOverflow::Roll => match NaiveDate::from_ymd_opt(year, month, day) {
+                Some(date) => Some(date),
+                None => {
+                    NaiveDate::from_ymd_opt(year, month, days_in_month(year, month))?.succ_opt()
+                }
+            },
+            DayOverflow::Skip => NaiveDate::from_ymd_opt(year, month, day),
+        }
+    }
+}
+
+impl std::fmt::Display for DayOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DayOverflow::Clamp => write!(f, "moved to the last day of the month when missing"),
+            DayOverflow::Roll => write!(f, "moved to the next day when missing"),
+            DayOverflow::Skip => write!(f, "skipped when missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hours_minutes_formats_right() {
+        let dt = TimeDelta::Hm(1, 20);
+        assert_eq!(&format!("{}", dt), "1h20m");
+
+        let dt = TimeDelta::Hm(0, 15);
+        assert_eq!(&format!("{}", dt), "15m");
+
+        let dt = TimeDelta::Hm(5, 0);
+        assert_eq!(&format!("{}", dt), "5h");
+    }
+
+    #[test]
+    fn time_delta_parses_displayed_format() {
+        for (input, expected) in &[
+            ("3 days", "3 days"),
+            ("2d", "2 days"),
+            ("3 business days", "3 business days"),
+            ("1bd", "1 business days"),
+            ("1h30m", "1h30m"),
+            ("45m", "45m"),
+            ("2h", "2h"),
+            ("90m", "90m"),
+        ] {
+            let delta = input.parse::<TimeDelta>().unwrap();
+            assert_eq!(&format!("{}", delta), expected);
+        }
+        for input in &[
+            "", "h", "2x", "-1h", "1h30", "m", "-1d", "0d", "0bd", "0m", "0h0m", "-5m",
+        ] {
+            assert!(
+                input.parse::<TimeDelta>().is_err(),
+                "'{}' was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn multi_annual_formats_right() {
+        let period = TimePeriod::MultiAnnual(vec![
+            (
+                AnnualDay {
+                    month: 1,
+                    day: 15,
+                    overflow: DayOverflow::Clamp,
+                },
+                NaiveTime::from_hms(9, 0, 0),
+            ),
+            (
+                AnnualDay {
+                    month: 10,
+                    day: 1,
+                    overflow: DayOverflow::Clamp,
+                },
+                NaiveTime::from_hms(18, 30, 0),
+            ),
+        ]);
+        assert_eq!(
+            &format!("{}", period),
+            "triggers multi-annually on 1.15. at 09:00, 10.1. at 18:30"
+        );
+    }
+
+    #[test]
+    fn multi_weekly_formats_right() {
+        let period = TimePeriod::MultiWeekly(
+            WeeklyDays {
+                weekdays: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri],
+                every_n_weeks: 1,
+                start: NaiveDate::from_ymd(2020, 1, 6),
+            },
+            NaiveTime::from_hms(7, 0, 0),
+        );
+        assert_eq!(
+            &format!("{}", period),
+            "triggers weekly on Mon, Wed, Fri at 07:00"
+        );
+
+        let days = WeeklyDays {
+            weekdays: vec![Weekday::Tue],
+            every_n_weeks: 2,
+            start: NaiveDate::from_ymd(2020, 1, 6),
+        };
+        assert_eq!(&format!("{}", days), "every 2 weeks on Tue from 6.1.2020");
+    }
+
+    #[test]
+    fn sub_daily_formats_right() {
+        let period = TimePeriod::SubDaily(DailyWindow {
+            every_minutes: 90,
+            from: NaiveTime::from_hms(9, 0, 0),
+            until: NaiveTime::from_hms(17, 0, 0),
+            weekdays: vec![Weekday::Mon, Weekday::Tue],
+        });
+        assert_eq!(
+            &format!("{}", period),
+            "triggers every 1h30m from 09:00 to 17:00 on Mon, Tue"
+        );
+
+        let window = DailyWindow {
+            every_minutes: 120,
+            from: NaiveTime::from_hms(22, 0, 0),
+            until: NaiveTime::from_hms(2, 0, 0),
+            weekdays: vec![],
+        };
+        assert_eq!(&format!("{}", window), "every 2h from 22:00 to 02:00");
+    }
+
+    #[test]
+    fn monthly_weekday_formats_right() {
+        let period = TimePeriod::MonthlyWeekday(
+            NthWeekday {
+                nth: 1,
+                weekday: Weekday::Mon,
+            },
+            NaiveTime::from_hms(9, 0, 0),
+        );
+        assert_eq!(
+            &format!("{}", period),
+            "triggers monthly on the first Mon at 09:00"
+        );
+
+        let day = NthWeekday {
+            nth: -2,
+            weekday: Weekday::Fri,
+        };
+        assert_eq!(&format!("{}", day), "second to last Fri");
+    }
+
+    #[test]
+    fn day_overflow_formats_right() {
+        let period = TimePeriod::Monthly(
+            MonthlyDay {
+                day: 31,
+                overflow: DayOverflow::Skip,
+            },
+            NaiveTime::from_hms(9, 0, 0),
+        );
+        assert_eq!(
+            &format!("{}", period),
+            "triggers monthly on 31. (skipped when missing) at 09:00"
+        );
+
+        let day = AnnualDay {
+            month: 2,
+            day: 29,
+            overflow: DayOverflow::Roll,
+        };
+        assert_eq!(
+            &format!("{}", day),
+            "2.29. (moved to the next day when missing)"
+        );
+
+        // The policy is not shown for days that exist on every month
+        let day = AnnualDay {
+            month: 3,
+            day: 31,
+            overflow: DayOverflow::Roll,
+        };
+        assert_eq!(&format!("{}", day), "3.31.");
     }
 }
@@ -1,9 +1,24 @@
+mod calendar;
+mod clock;
+mod cron;
 mod event;
+mod holidays;
 mod interval;
+mod rrule;
+mod season;
+mod stats;
 mod time;
 mod tracked_event;
+mod vacation;
 
+pub use clock::*;
+pub use cron::*;
 pub use event::*;
+pub use holidays::*;
 pub use interval::*;
+pub use rrule::*;
+pub use season::*;
+pub use stats::*;
 pub use time::*;
 pub use tracked_event::*;
+pub use vacation::*;
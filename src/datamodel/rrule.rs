@@ -0,0 +1,733 @@
+use crate::datamodel::calendar::{add_months, days_in_month, days_in_year};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Upper bound in years for a stretch of recurrence periods without an
+/// instance. Covers the longest gap between leap days, eg.
+/// "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29", and stops rules that never produce
+/// an instance, eg. "FREQ=MONTHLY;BYDAY=5MO;BYMONTHDAY=1", from looping forever.
+const MAX_EMPTY_YEARS: i64 = 8;
+
+/// A recurrence rule as defined by RFC 5545, eg. "FREQ=MONTHLY;BYDAY=-1FR".
+///
+/// The rule may be preceded by a DTSTART line in the event's local time, in
+/// which case the recurrence is anchored to that time. Otherwise the recurrence
+/// is anchored to the day of the anchor it is evaluated with, eg. the time the
+/// event was registered, and triggers at midnight unless BYHOUR / BYMINUTE are
+/// given.
+///
+/// Supports the frequencies DAILY, WEEKLY, MONTHLY and YEARLY, and the rule
+/// parts INTERVAL, COUNT, UNTIL, BYMONTH, BYMONTHDAY, BYDAY, BYHOUR, BYMINUTE,
+/// BYSETPOS and WKST.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct RRule {
+    source: String,
+    dtstart: Option<NaiveDateTime>,
+    freq: Frequency,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<NaiveDateTime>,
+    by_month: Vec<u32>,
+    by_month_day: Vec<i32>,
+    by_day: Vec<(Option<i32>, Weekday)>,
+    by_hour: Vec<u32>,
+    by_minute: Vec<u32>,
+    by_set_pos: Vec<i32>,
+    week_start: Weekday,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Represents a recurrence rule that could not be parsed or is not supported.
+/// Parameters: reason.
+#[derive(Debug, Clone)]
+pub struct RRuleError(pub String);
+
+impl RRule {
+    /// Returns the first occurrence of the rule after `from`, or None if the
+    /// rule does not produce any more occurrences. Rules without a DTSTART are
+    /// anchored to the start of the day of `anchor`, which should stay the same
+    /// between calls so that intervals and defaulted rule parts don't drift.
+    pub fn next_after(
+        &self,
+        from: &NaiveDateTime,
+        anchor: &NaiveDateTime,
+    ) -> Option<NaiveDateTime> {
+        let from = *from;
+        let anchor = self
+            .dtstart
+            .unwrap_or_else(|| anchor.date().and_time(NaiveTime::from_hms(0, 0, 0)));
+
+        // Counted rules need to be evaluated from the start to know which occurrence is
+        // the last one, others can skip directly to the period of `from`
+        let first_period = if self.count.is_some() || from < anchor {
+            0
+        } else {
+            self.period_offset(anchor.date(), from.date())
+                .div_euclid(self.interval as i64)
+        };
+
+        let mut n_occurrences = 0;
+        let mut empty_periods = 0;
+        for period in first_period.. {
+            let (start, end) = self.period_bounds(anchor.date(), period * self.interval as i64)?;
+            if let Some(until) = self.until {
+                if start.and_hms(0, 0, 0) > until {
+                    return None;
+                }
+            }
+
+            let instances = self.expand(&anchor, start, end);
+            if instances.is_empty() {
+                empty_periods += 1;
+                if empty_periods > self.max_empty_periods() {
+                    return None;
+                }
+            } else {
+                empty_periods = 0;
+            }
+            for instance in instances {
+                if instance < anchor {
+                    continue;
+                }
+                if let Some(until) = self.until {
+                    if instance > until {
+                        return None;
+                    }
+                }
+                n_occurrences += 1;
+                if let Some(count) = self.count {
+                    if n_occurrences > count {
+                        return None;
+                    }
+                }
+                if instance > from {
+                    return Some(instance);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true if the rule ends by COUNT or UNTIL
+    pub fn has_end(&self) -> bool {
+        self.count.is_some() || self.until.is_some()
+    }
+
+    /// Returns an estimate of the average time between occurrences
+    pub fn to_duration_heuristic(&self) -> Duration {
+        use Frequency::*;
+        let period_days = match self.freq {
+            Daily => 1,
+            Weekly => 7,
+            Monthly => 30,
+            Yearly => 365,
+        } * self.interval as i64;
+
+        // Expanded weekdays produce about 4 instances per month and 52 per year, unless
+        // picked by ordinal
+        let by_day_instances = |per_weekday: i64| {
+            self.by_day
+                .iter()
+                .map(|(nth, _)| if nth.is_some() { 1 } else { per_weekday })
+                .sum::<i64>()
+        };
+        let days_per_period = match self.freq {
+            Daily => 1,
+            Weekly => self.by_day.len().max(1) as i64,
+            Monthly if !self.by_month_day.is_empty() => self.by_month_day.len() as i64,
+            Monthly if !self.by_day.is_empty() => by_day_instances(4),
+            Monthly => 1,
+            Yearly if !self.by_month_day.is_empty() => {
+                (self.by_month_day.len() * self.by_month.len().max(1)) as i64
+            }
+            Yearly if !self.by_day.is_empty() && !self.by_month.is_empty() => {
+                by_day_instances(4) * self.by_month.len() as i64
+            }
+            Yearly if !self.by_day.is_empty() => by_day_instances(52),
+            Yearly => self.by_month.len().max(1) as i64,
+        };
+        let instances_per_period = match self.by_set_pos.len() {
+            0 => {
+                days_per_period
+                    * self.by_hour.len().max(1) as i64
+                    * self.by_minute.len().max(1) as i64
+            }
+            n => n as i64,
+        };
+
+        Duration::minutes(period_days * 24 * 60 / instances_per_period.max(1))
+    }
+
+    /// Returns the amount of consecutive periods without an instance after
+    /// which the rule is considered to produce no more instances
+    fn max_empty_periods(&self) -> i64 {
+        use Frequency::*;
+        MAX_EMPTY_YEARS
+            * match self.freq {
+                Daily => 366,
+                Weekly => 53,
+                Monthly => 12,
+                Yearly => 1,
+            }
+    }
+
+    /// Returns the amount of base periods (days, weeks, months, years) between
+    /// the periods containing `anchor` and `date`
+    fn period_offset(&self, anchor: NaiveDate, date: NaiveDate) -> i64 {
+        use Frequency::*;
+        match self.freq {
+            Daily => date.signed_duration_since(anchor).num_days(),
+            Weekly => {
+                self.week_start_of(date)
+                    .signed_duration_since(self.week_start_of(anchor))
+                    .num_days()
+                    / 7
+            }
+            Monthly => {
+                (date.year() as i64 * 12 + date.month0() as i64)
+                    - (anchor.year() as i64 * 12 + anchor.month0() as i64)
+            }
+            Yearly => date.year() as i64 - anchor.year() as i64,
+        }
+    }
+
+    /// Returns the first day and the day after the last day of the base period
+    /// that is `offset` periods after the period containing `anchor`
+    fn period_bounds(&self, anchor: NaiveDate, offset: i64) -> Option<(NaiveDate, NaiveDate)> {
+        use Frequency::*;
+        match self.freq {
+            Daily => {
+                let start = anchor.checked_add_signed(Duration::days(offset))?;
+                Some((start, start.succ_opt()?))
+            }
+            Weekly => {
+                let start = self
+                    .week_start_of(anchor)
+                    .checked_add_signed(Duration::weeks(offset))?;
+                Some((start, start.checked_add_signed(Duration::weeks(1))?))
+            }
+            Monthly => {
+                let (year, month) = add_months(anchor.year(), anchor.month(), offset)?;
+                let (next_year, next_month) = add_months(year, month, 1)?;
+                Some((
+                    NaiveDate::from_ymd_opt(year, month, 1)?,
+                    NaiveDate::from_ymd_opt(next_year, next_month, 1)?,
+                ))
+            }
+            Yearly => {
+                let year = i32::try_from(anchor.year() as i64 + offset).ok()?;
+                Some((
+                    NaiveDate::from_ymd_opt(year, 1, 1)?,
+                    NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+                ))
+            }
+        }
+    }
+
+    fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        let days_since_week_start = (date.weekday().num_days_from_monday() + 7
+            - self.week_start.num_days_from_monday())
+            % 7;
+        date - Duration::days(days_since_week_start as i64)
+    }
+
+    /// Returns the sorted instances of the rule within the dates [start, end)
+    fn expand(
+        &self,
+        anchor: &NaiveDateTime,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Vec<NaiveDateTime> {
+        let hours = match self.by_hour.len() {
+            0 => vec![anchor.hour()],
+            _ => self.by_hour.clone(),
+        };
+        let minutes = match self.by_minute.len() {
+            0 => vec![anchor.minute()],
+            _ => self.by_minute.clone(),
+        };
+        let mut times = hours
+            .iter()
+            .flat_map(|&h| {
+                minutes
+                    .iter()
+                    .map(move |&m| NaiveTime::from_hms(h, m, anchor.second()))
+            })
+            .collect::<Vec<NaiveTime>>();
+        times.sort();
+        times.dedup();
+
+        let mut instances = vec![];
+        let mut date = start;
+        while date < end {
+            if self.matches_date(&anchor.date(), &date) {
+                instances.extend(times.iter().map(|t| date.and_time(*t)));
+            }
+            date = match date.succ_opt() {
+                Some(d) => d,
+                None => break,
+            };
+        }
+
+        if self.by_set_pos.is_empty() {
+            return instances;
+        }
+        let len = instances.len() as i32;
+        let mut selected = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let idx = if pos > 0 { pos - 1 } else { len + pos };
+                if idx >= 0 && idx < len {
+                    Some(instances[idx as usize])
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<NaiveDateTime>>();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+
+    /// Returns true if the rule produces instances on `date`. Rule parts that
+    /// are not given default to the corresponding value of the anchor.
+    fn matches_date(&self, anchor: &NaiveDate, date: &NaiveDate) -> bool {
+        use Frequency::*;
+
+        if !self.by_month.is_empty() {
+            if !self.by_month.contains(&date.month()) {
+                return false;
+            }
+        } else if self.freq == Yearly
+            && self.by_month_day.is_empty()
+            && self.by_day.is_empty()
+            && date.month() != anchor.month()
+        {
+            return false;
+        }
+
+        if !self.by_month_day.is_empty() {
+            let n_days = days_in_month(date.year(), date.month()) as i32;
+            let day = date.day() as i32;
+            if !self
+                .by_month_day
+                .iter()
+                .any(|&d| d == day || d == day - n_days - 1)
+            {
+                return false;
+            }
+        } else if (self.freq == Yearly || self.freq == Monthly)
+            && self.by_day.is_empty()
+            && date.day() != anchor.day()
+        {
+            return false;
+        }
+
+        if !self.by_day.is_empty() {
+            if !self.by_day.iter().any(|(nth, weekday)| {
+                date.weekday() == *weekday
+                    && match nth {
+                        None => true,
+                        Some(n) => self.is_nth_weekday(date, *n),
+                    }
+            }) {
+                return false;
+            }
+        } else if self.freq == Weekly && date.weekday() != anchor.weekday() {
+            return false;
+        }
+
+        true
+    }
+
+    /// Returns true if `date` is the nth of its weekday within the month or the
+    /// year, counting from the end if n is negative
+    fn is_nth_weekday(&self, date: &NaiveDate, n: i32) -> bool {
+        let within_month = self.freq == Frequency::Monthly || !self.by_month.is_empty();
+        let (day, n_days) = if within_month {
+            (date.day(), days_in_month(date.year(), date.month()))
+        } else {
+            (date.ordinal(), days_in_year(date.year()))
+        };
+        let from_start = ((day - 1) / 7 + 1) as i32;
+        let from_end = -(((n_days - day) / 7 + 1) as i32);
+        n == from_start || n == from_end
+    }
+}
+
+impl FromStr for RRule {
+    type Err = RRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut dtstart = None;
+        let mut rule = None;
+        for line in s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let upper = line.to_uppercase();
+            if upper.starts_with("DTSTART") {
+                // Parameters are separated from the value by the last colon. The start is
+                // read on the event's wall clock, so times in other zones are rejected rather
+                // than shifted.
+                let (params, value) = upper.rsplit_once(':').unwrap_or(("", &upper));
+                if params.contains("TZID=") || value.ends_with('Z') {
+                    return Err(RRuleError(format!(
+                        "DTSTART '{}' is not in local time, give it without TZID or 'Z' and set \
+                         the event's time zone instead",
+                        line
+                    )));
+                }
+                dtstart = Some(parse_datetime(value)?);
+            } else if let Some(r) = upper.strip_prefix("RRULE:") {
+                rule = Some(r.to_string());
+            } else if upper.contains("FREQ=") {
+                rule = Some(upper);
+            } else {
+                return Err(RRuleError(format!("unrecognized line '{}'", line)));
+            }
+        }
+        let rule = match rule {
+            Some(r) => r,
+            None => return Err(RRuleError("no RRULE given".to_string())),
+        };
+
+        let mut freq = None;
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut by_month = vec![];
+        let mut by_month_day: Vec<i32> = vec![];
+        let mut by_day = vec![];
+        let mut by_hour = vec![];
+        let mut by_minute = vec![];
+        let mut by_set_pos = vec![];
+        let mut week_start = Weekday::Mon;
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let mut kv = part.splitn(2, '=');
+            let key = kv.next().unwrap_or("");
+            let value = match kv.next() {
+                Some(v) => v,
+                None => return Err(RRuleError(format!("rule part '{}' has no value", part))),
+            };
+            match key {
+                "FREQ" => {
+                    freq = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(RRuleError(format!("unsupported FREQ '{}'", other))),
+                    })
+                }
+                "INTERVAL" => interval = parse_number(key, value, 1, i32::MAX)? as u32,
+                "COUNT" => count = Some(parse_number(key, value, 1, i32::MAX)? as u32),
+                // The end is read on the event's wall clock like DTSTART
+                "UNTIL" if value.ends_with('Z') => {
+                    return Err(RRuleError(format!(
+                        "UNTIL '{}' is not in local time, give it without 'Z' and set the \
+                         event's time zone instead",
+                        value
+                    )))
+                }
+                "UNTIL" => until = Some(parse_datetime(value)?),
+                "BYMONTH" => by_month = parse_list(key, value, 1, 12, false)?,
+                "BYMONTHDAY" => by_month_day = parse_list(key, value, -31, 31, true)?,
+                "BYHOUR" => by_hour = parse_list(key, value, 0, 23, false)?,
+                "BYMINUTE" => by_minute = parse_list(key, value, 0, 59, false)?,
+                "BYSETPOS" => by_set_pos = parse_list(key, value, -366, 366, true)?,
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(parse_by_day)
+                        .collect::<Result<_, _>>()?
+                }
+                "WKST" => week_start = parse_weekday(value)?,
+                other => return Err(RRuleError(format!("unsupported rule part '{}'", other))),
+            }
+        }
+
+        let freq = match freq {
+            Some(f) => f,
+            None => return Err(RRuleError("FREQ is required".to_string())),
+        };
+        if count.is_some() && until.is_some() {
+            return Err(RRuleError("COUNT and UNTIL cannot be combined".to_string()));
+        }
+        if !by_month.is_empty() && !by_month_day.is_empty() {
+            // Counted on a leap year so that February has its longest length
+            let longest = by_month
+                .iter()
+                .map(|&month| days_in_month(2000, month))
+                .max()
+                .unwrap_or(31);
+            if by_month_day.iter().all(|d| d.unsigned_abs() > longest) {
+                return Err(RRuleError(
+                    "BYMONTHDAY does not fall on any day of BYMONTH".to_string(),
+                ));
+            }
+        }
+        if count.is_some() && dtstart.is_none() {
+            return Err(RRuleError("COUNT requires a DTSTART".to_string()));
+        }
+        if freq == Frequency::Weekly && !by_month_day.is_empty() {
+            return Err(RRuleError(
+                "BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string(),
+            ));
+        }
+        if by_day.iter().any(|(nth, _)| nth.is_some())
+            && (freq == Frequency::Daily || freq == Frequency::Weekly)
+        {
+            return Err(RRuleError(
+                "numbered BYDAY is only valid with FREQ=MONTHLY or FREQ=YEARLY".to_string(),
+            ));
+        }
+        if !by_set_pos.is_empty()
+            && by_month.is_empty()
+            && by_month_day.is_empty()
+            && by_day.is_empty()
+            && by_hour.is_empty()
+            && by_minute.is_empty()
+        {
+            return Err(RRuleError(
+                "BYSETPOS requires another BYxxx rule part".to_string(),
+            ));
+        }
+
+        Ok(RRule {
+            source: s.lines().map(|l| l.trim()).collect::<Vec<_>>().join("\n"),
+            dtstart,
+            freq,
+            interval,
+            count,
+            until,
+            by_month,
+            by_month_day,
+            by_day,
+            by_hour,
+            by_minute,
+            by_set_pos,
+            week_start,
+        })
+    }
+}
+
+impl TryFrom<String> for RRule {
+    type Error = RRuleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<RRule> for String {
+    fn from(rule: RRule) -> String {
+        rule.source
+    }
+}
+
+impl fmt::Display for RRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source.replace('\n', " "))
+    }
+}
+
+impl error::Error for RRuleError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl fmt::Display for RRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid recurrence rule: {}", self.0)
+    }
+}
+
+fn parse_datetime(s: &str) -> Result<NaiveDateTime, RRuleError> {
+    NaiveDateTime::parse_from_str(s, "%Y%m%dT%H%M%S")
+        .or_else(|_| NaiveDate::parse_from_str(s, "%Y%m%d").map(|d| d.and_hms(0, 0, 0)))
+        .map_err(|_| RRuleError(format!("cannot parse date-time from '{}'", s)))
+}
+
+fn parse_number(key: &str, s: &str, min: i32, max: i32) -> Result<i32, RRuleError> {
+    match s.parse::<i32>() {
+        Ok(n) if n >= min && n <= max => Ok(n),
+        _ => Err(RRuleError(format!(
+            "{} value '{}' is not a number between {} and {}",
+            key, s, min, max
+        ))),
+    }
+}
+
+fn parse_list<T>(key: &str, s: &str, min: i32, max: i32, signed: bool) -> Result<Vec<T>, RRuleError>
+where
+    T: TryFrom<i32>,
+{
+    s.split(',')
+        .map(|item| {
+            let n = parse_number(key, item, min, max)?;
+            if signed && n == 0 {
+                return Err(RRuleError(format!("{} value cannot be zero", key)));
+            }
+            T::try_from(n).map_err(|_| RRuleError(format!("invalid {} value '{}'", key, item)))
+        })
+        .collect()
+}
+
+fn parse_by_day(s: &str) -> Result<(Option<i32>, Weekday), RRuleError> {
+    if s.len() < 2 {
+        return Err(RRuleError(format!("cannot parse weekday from '{}'", s)));
+    }
+    let (nth, weekday) = s.split_at(s.len() - 2);
+    let nth = match nth {
+        "" => None,
+        nth => match parse_number("BYDAY", nth.trim_start_matches('+'), -53, 53)? {
+            0 => return Err(RRuleError("BYDAY ordinal cannot be zero".to_string())),
+            n => Some(n),
+        },
+    };
+    Ok((nth, parse_weekday(weekday)?))
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, RRuleError> {
+    match s {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        other => Err(RRuleError(format!("cannot parse weekday from '{}'", other))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn dt(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn nth_weekday_of_month() {
+        let rule = "FREQ=MONTHLY;BYDAY=2TU;BYHOUR=9;BYMINUTE=0"
+            .parse::<RRule>()
+            .unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-01-15 12:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-02-11 09:00"))
+        );
+
+        let rule = "RRULE:FREQ=MONTHLY;BYDAY=-1FR".parse::<RRule>().unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-02-01 12:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-02-28 00:00"))
+        );
+    }
+
+    #[test]
+    fn last_business_day_of_month() {
+        let rule = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1;BYHOUR=16;BYMINUTE=0"
+            .parse::<RRule>()
+            .unwrap();
+        // May 31st 2020 is a Sunday
+        assert_eq!(
+            rule.next_after(&dt("2020-05-02 12:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-05-29 16:00"))
+        );
+    }
+
+    #[test]
+    fn every_third_week_on_two_days() {
+        let rule = "DTSTART:20200106T083000\nRRULE:FREQ=WEEKLY;INTERVAL=3;BYDAY=MO,TH"
+            .parse::<RRule>()
+            .unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-01-07 00:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-01-09 08:30"))
+        );
+        assert_eq!(
+            rule.next_after(&dt("2020-01-10 00:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-01-27 08:30"))
+        );
+    }
+
+    #[test]
+    fn count_and_until_end_recurrence() {
+        let rule = "DTSTART:20200101T100000\nRRULE:FREQ=DAILY;COUNT=3"
+            .parse::<RRule>()
+            .unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-01-02 11:00"), &dt("2020-01-01 00:00")),
+            Some(dt("2020-01-03 10:00"))
+        );
+        assert_eq!(
+            rule.next_after(&dt("2020-01-03 11:00"), &dt("2020-01-01 00:00")),
+            None
+        );
+
+        let rule = "FREQ=DAILY;UNTIL=20200103T000000".parse::<RRule>().unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-01-03 11:00"), &dt("2020-01-01 00:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn rare_and_impossible_instances() {
+        // The next leap day after 2096 is in 2104
+        let rule = "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29"
+            .parse::<RRule>()
+            .unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2096-03-01 00:00"), &dt("2096-01-01 00:00")),
+            Some(dt("2104-02-29 00:00"))
+        );
+
+        // There's never a fifth Monday on the first day of a month
+        let rule = "FREQ=MONTHLY;BYDAY=5MO;BYMONTHDAY=1"
+            .parse::<RRule>()
+            .unwrap();
+        assert_eq!(
+            rule.next_after(&dt("2020-01-01 00:00"), &dt("2020-01-01 00:00")),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for rule in &[
+            "",
+            "INTERVAL=2",
+            "FREQ=FORTNIGHTLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=WEEKLY;BYDAY=2TU",
+            "FREQ=DAILY;COUNT=3",
+            "FREQ=MONTHLY;BYSETPOS=1",
+            "FREQ=DAILY;BYWEEKNO=1",
+            "DTSTART;TZID=Europe/Helsinki:20200101T090000\nRRULE:FREQ=DAILY",
+            "DTSTART:20200101T090000Z\nRRULE:FREQ=DAILY",
+            "FREQ=DAILY;UNTIL=20200103T000000Z",
+            "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=YEARLY;BYMONTH=4,6;BYMONTHDAY=31,-31",
+        ] {
+            assert!(rule.parse::<RRule>().is_err(), "'{}' was accepted", rule);
+        }
+        assert!(serde_yaml::from_str::<RRule>("FREQ=DAILY;BYHOUR=25").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::prelude::*;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A yearly range of days during which an event is active, both ends
+/// inclusive. Written as month.day. pairs like annual days, eg. "5.1.-9.30."
+/// for May to September. A season that ends before it starts wraps over the
+/// turn of the year, eg. "12.1.-2.29." for the winter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Season {
+    /// (month, day)
+    starts: (u32, u32),
+    /// (month, day)
+    ends: (u32, u32),
+}
+
+/// Represents a season that could not be parsed. Parameters: input.
+#[derive(Debug, Clone)]
+pub struct SeasonError(pub String);
+
+impl Season {
+    /// Returns true if the date is within the season on any year
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        let day = (date.month(), date.day());
+        match self.starts <= self.ends {
+            true => self.starts <= day && day <= self.ends,
+            false => self.starts <= day || day <= self.ends,
+        }
+    }
+    /// Returns the first day of the season that comes after `date`
+    pub fn next_start_after(&self, date: NaiveDate) -> NaiveDate {
+        let (month, day) = self.starts;
+        // A season starting on the leap day starts on the 1st of March on other years
+        let start_on = |year| {
+            NaiveDate::from_ymd_opt(year, month, day)
+                .unwrap_or_else(|| NaiveDate::from_ymd(year, 3, 1))
+        };
+        match start_on(date.year()) {
+            start if start > date => start,
+            _ => start_on(date.year() + 1),
+        }
+    }
+}
+
+impl FromStr for Season {
+    type Err = SeasonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || SeasonError(s.to_string());
+        // Parses a "month.day." pair, checked against a leap year so that 2.29. is valid
+        let parse_day = |s: &str| -> Result<(u32, u32), SeasonError> {
+            let parts = s
+                .trim()
+                .trim_end_matches('.')
+                .split('.')
+                .collect::<Vec<&str>>();
+            match parts[..] {
+                [month, day] => {
+                    let month = month.trim().parse().map_err(|_| err())?;
+                    let day = day.trim().parse().map_err(|_| err())?;
+                    NaiveDate::from_ymd_opt(2000, month, day)
+                        .map(|_| (month, day))
+                        .ok_or_else(err)
+                }
+                _ => Err(err()),
+            }
+        };
+        match s.split('-').collect::<Vec<&str>>()[..] {
+            [starts, ends] => Ok(Season {
+                starts: parse_day(starts)?,
+                ends: parse_day(ends)?,
+            }),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl TryFrom<String> for Season {
+    type Error = SeasonError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Season> for String {
+    fn from(season: Season) -> String {
+        season.to_string()
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.-{}.{}.",
+            self.starts.0, self.starts.1, self.ends.0, self.ends.1
+        )
+    }
+}
+
+impl std::error::Error for SeasonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl fmt::Display for SeasonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid season '{}', expected month.day. pairs, eg. \"5.1.-9.30.\"",
+            self.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn season_parses_displayed_format() {
+        let season = "5.1.-9.30.".parse::<Season>().unwrap();
+        assert_eq!(season.to_string(), "5.1.-9.30.");
+        assert_eq!("5.1-9.30".parse::<Season>().unwrap(), season);
+        assert!("2.30.-3.1.".parse::<Season>().is_err());
+        assert!("5.1.".parse::<Season>().is_err());
+    }
+
+    #[test]
+    fn winter_season_wraps_over_new_year() {
+        let winter = "12.1.-2.29.".parse::<Season>().unwrap();
+        assert!(winter.contains(NaiveDate::from_ymd(2020, 12, 24)));
+        assert!(winter.contains(NaiveDate::from_ymd(2021, 2, 28)));
+        assert!(!winter.contains(NaiveDate::from_ymd(2021, 3, 1)));
+        assert_eq!(
+            winter.next_start_after(NaiveDate::from_ymd(2021, 1, 10)),
+            NaiveDate::from_ymd(2021, 12, 1)
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::datamodel::{HistoryAction, HistoryEntry};
+use crate::prelude::*;
+use chrono::Duration;
+
+/// How a single occurrence of an event was resolved
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    /// Time of completion or skipping
+    pub time: LocalTime,
+    pub kind: OutcomeKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutcomeKind {
+    /// Completed the given time after triggering
+    Completed(Duration),
+    Skipped,
+}
+
+/// Statistics over the occurrences of one or several events
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventStats {
+    pub completed: usize,
+    pub skipped: usize,
+    /// Occurrences that have triggered but are not completed yet
+    pub pending: usize,
+    /// Sum of the delays between triggering and completion
+    pub total_delay: Duration,
+    /// Completed occurrences since the last skipped one
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+/// Resolves the occurrences in the history of an event. Completions are
+/// matched to the earliest pending trigger, completions without one count as
+/// completed on time. Returns the outcomes in order of resolution and the
+/// number of occurrences that are still pending.
+pub fn outcomes(history: &[HistoryEntry]) -> (Vec<Outcome>, usize) {
+    let mut pending = std::collections::VecDeque::new();
+    let mut outcomes = vec![];
+    for entry in history {
+        match entry.action {
+            HistoryAction::Trigger => pending.push_back(entry.time.0),
+            HistoryAction::Complete => {
+                // Backdated completions may precede the trigger, they count as on time
+                let delay = match pending.pop_front() {
+                    Some(triggered) => std::cmp::max(
+                        entry.time.signed_duration_since(triggered),
+                        Duration::zero(),
+                    ),
+                    None => Duration::zero(),
+                };
+                outcomes.push(Outcome {
+                    time: entry.time.0,
+                    kind: OutcomeKind::Completed(delay),
+                });
+            }
+            HistoryAction::Skip => outcomes.push(Outcome {
+                time: entry.time.0,
+                kind: OutcomeKind::Skipped,
+            }),
+        }
+    }
+    (outcomes, pending.len())
+}
+
+impl EventStats {
+    /// Computes the statistics from outcomes in order of resolution
+    pub fn from_outcomes(outcomes: &[Outcome], pending: usize) -> EventStats {
+        let mut stats = EventStats {
+            completed: 0,
+            skipped: 0,
+            pending,
+            total_delay: Duration::zero(),
+            current_streak: 0,
+            longest_streak: 0,
+        };
+        for outcome in outcomes {
+            match outcome.kind {
+                OutcomeKind::Completed(delay) => {
+                    stats.completed += 1;
+                    stats.total_delay = stats.total_delay + delay;
+                    stats.current_streak += 1;
+                    stats.longest_streak =
+                        std::cmp::max(stats.longest_streak, stats.current_streak);
+                }
+                OutcomeKind::Skipped => {
+                    stats.skipped += 1;
+                    stats.current_streak = 0;
+                }
+            }
+        }
+        stats
+    }
+    /// Returns the fraction of the resolved occurrences that were completed,
+    /// or None if nothing has been resolved yet
+    pub fn completion_rate(&self) -> Option<f64> {
+        match self.completed + self.skipped {
+            0 => None,
+            n => Some(self.completed as f64 / n as f64),
+        }
+    }
+    /// Returns the average delay between triggering and completion, or None
+    /// if nothing has been completed yet
+    pub fn average_delay(&self) -> Option<Duration> {
+        match self.completed {
+            0 => None,
+            n => Some(self.total_delay / n as i32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::datamodel::Time;
+    use chrono::{DateTime, Local};
+
+    fn entry(time: &str, action: HistoryAction) -> HistoryEntry {
+        let time: DateTime<Local> = DateTime::parse_from_rfc3339(time).unwrap().into();
+        HistoryEntry {
+            time: Time(time),
+            action,
+            occurrence: None,
+        }
+    }
+
+    #[test]
+    fn completions_match_earliest_trigger() {
+        let history = [
+            entry("2020-01-01T09:00:00+00:00", HistoryAction::Trigger),
+            entry("2020-01-02T09:00:00+00:00", HistoryAction::Trigger),
+            entry("2020-01-02T10:00:00+00:00", HistoryAction::Complete),
+            entry("2020-01-03T09:00:00+00:00", HistoryAction::Trigger),
+            // Both of the pending occurrences completed at once
+            entry("2020-01-04T08:00:00+00:00", HistoryAction::Complete),
+            entry("2020-01-04T08:00:00+00:00", HistoryAction::Complete),
+            // Completed without a trigger, ie. ahead of time
+            entry("2020-01-05T08:00:00+00:00", HistoryAction::Complete),
+        ];
+        let (outcomes, pending) = outcomes(&history);
+        let delays = outcomes
+            .iter()
+            .map(|outcome| match outcome.kind {
+                OutcomeKind::Completed(delay) => delay.num_hours(),
+                OutcomeKind::Skipped => unreachable!(),
+            })
+            .collect::<Vec<i64>>();
+        assert_eq!(delays, vec![25, 47, 23, 0]);
+        assert_eq!(pending, 0);
+
+        let stats = EventStats::from_outcomes(&outcomes, pending);
+        assert_eq!(stats.current_streak, 4);
+        assert_eq!(stats.completion_rate(), Some(1.));
+    }
+}
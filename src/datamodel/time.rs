@@ -1,7 +1,11 @@
 use crate::prelude::*;
-use chrono::{Local, Timelike};
+use chrono::offset::{LocalResult, Offset};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
 use serde::{Deserialize, Serialize, Serializer};
+use std::error;
+use std::fmt;
 use std::ops::Deref;
+use std::str::FromStr;
 
 // Implements a custom serializer with lower precision timestamps, note that the
 // full timestamp must exist in runtime, but the serialized format can do with
@@ -34,8 +38,146 @@ impl From<LocalTime> for Time {
     }
 }
 
-impl Time {
-    pub fn now() -> Time {
-        Time(Local::now())
+/// Returns the instant at which the wall-clock time `naive` occurs in `tz`.
+///
+/// Wall-clock times that occur twice because of a backward transition, eg. at
+/// the end of daylight saving time, resolve to the earlier instant. Times
+/// that are skipped by a forward transition resolve using the offset from
+/// before the transition, ie. they are pushed forward by the length of the
+/// gap.
+pub fn resolve_local<Tz: TimeZone>(tz: &Tz, naive: &NaiveDateTime) -> DateTime<Tz> {
+    match tz.from_local_datetime(naive) {
+        LocalResult::Single(t) => t,
+        LocalResult::Ambiguous(earlier, _later) => earlier,
+        LocalResult::None => {
+            // Time zone transitions are far enough apart that the offset a day earlier is
+            // the one in effect before the gap
+            let offset_before = tz
+                .offset_from_utc_datetime(&(*naive - Duration::days(1)))
+                .fix();
+            let utc = *naive - Duration::seconds(offset_before.local_minus_utc() as i64);
+            tz.from_utc_datetime(&utc)
+        }
+    }
+}
+
+/// A point in time given by the user relative to the current time, eg.
+/// "yesterday 18:30", "9:00" for today or "2026-10-14T09:00"
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeSpec {
+    source: String,
+    date: DateSpec,
+    /// The time of day, the current time of day is used if not set
+    time: Option<NaiveTime>,
+}
+
+#[derive(Clone, Debug)]
+enum DateSpec {
+    DaysAgo(i64),
+    On(NaiveDate),
+}
+
+/// Represents a time that could not be parsed. Parameters: reason.
+#[derive(Debug, Clone)]
+pub struct TimeSpecError(pub String);
+
+const SPEC_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y"];
+const SPEC_TIME_FORMATS: &[&str] = &["%H:%M", "%H:%M:%S", "%H.%M"];
+
+impl TimeSpec {
+    /// Returns the instant the spec refers to in the local time zone when the
+    /// current time is `now`
+    pub fn resolve(&self, now: &LocalTime) -> LocalTime {
+        let date = match self.date {
+            DateSpec::DaysAgo(days) => now.date().naive_local() - Duration::days(days),
+            DateSpec::On(date) => date,
+        };
+        let time = self.time.unwrap_or_else(|| now.time());
+        resolve_local(&Local, &date.and_time(time))
+    }
+}
+
+impl FromStr for TimeSpec {
+    type Err = TimeSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_time = |s: &str| {
+            SPEC_TIME_FORMATS
+                .iter()
+                .find_map(|fmt| NaiveTime::parse_from_str(s, fmt).ok())
+                .ok_or_else(|| TimeSpecError(format!("cannot parse a time of day from '{}'", s)))
+        };
+        let parse_date = |s: &str| match s.to_lowercase().as_str() {
+            "now" | "today" => Some(DateSpec::DaysAgo(0)),
+            "yesterday" => Some(DateSpec::DaysAgo(1)),
+            _ => SPEC_DATE_FORMATS
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+                .map(DateSpec::On),
+        };
+
+        // Accept the ISO 8601 form with a 'T' between the date and the time
+        let tokens = match s.trim().splitn(2, 'T').collect::<Vec<&str>>()[..] {
+            [date, time] if parse_date(date).is_some() => vec![date, time],
+            _ => s.split_whitespace().collect(),
+        };
+        let (date, time) = match tokens[..] {
+            [] => return Err(TimeSpecError("no time given".to_string())),
+            [token] => match parse_date(token) {
+                Some(date) => (date, None),
+                // A lone time of day is today
+                None => (DateSpec::DaysAgo(0), Some(parse_time(token)?)),
+            },
+            [date, time] => match parse_date(date) {
+                Some(date) => (date, Some(parse_time(time)?)),
+                None => {
+                    return Err(TimeSpecError(format!(
+                        "cannot parse a date from '{}'",
+                        date
+                    )))
+                }
+            },
+            _ => return Err(TimeSpecError(format!("too many parts in '{}'", s))),
+        };
+
+        Ok(TimeSpec {
+            source: tokens.join(" "),
+            date,
+            time,
+        })
+    }
+}
+
+impl TryFrom<String> for TimeSpec {
+    type Error = TimeSpecError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeSpec> for String {
+    fn from(spec: TimeSpec) -> String {
+        spec.source
+    }
+}
+
+impl fmt::Display for TimeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl error::Error for TimeSpecError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        // Generic error, underlying cause isn't tracked.
+        None
+    }
+}
+
+impl fmt::Display for TimeSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid time: {}", self.0)
     }
 }
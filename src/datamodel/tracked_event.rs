@@ -1,8 +1,12 @@
 use crate::datamodel::*;
 use crate::prelude::*;
-use chrono::{Datelike, Duration, FixedOffset, Local, NaiveDate};
+use chrono::{Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
 
+/// Number of seasons that are looked ahead for the next occurrence of an
+/// event before giving up
+const MAX_SEASON_JUMPS: usize = 16;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TrackedEvent(pub EventData, pub Status);
 
@@ -24,37 +28,137 @@ impl TrackedEvent {
     pub fn is_completed(&self) -> bool {
         self.is_done()
     }
+    pub fn is_finished(&self) -> bool {
+        self.1.is_finished()
+    }
+    pub fn is_snoozed(&self) -> bool {
+        self.1.is_snoozed()
+    }
+    pub fn is_paused(&self) -> bool {
+        self.1.is_paused()
+    }
     pub fn text(&self) -> &str {
         self.0.text()
     }
-    pub fn update(&mut self) {
-        let now = Local::now();
+    /// Returns the number of occurrences waiting for completion
+    pub fn pending_occurrences(&self) -> usize {
+        match self.is_triggered() || self.is_snoozed() {
+            true => self.1.trigger_times().len(),
+            false => 0,
+        }
+    }
+    /// Triggers or finishes the event as it should be at time `now`. Every
+    /// occurrence since the previous update is walked through in order, each
+    /// triggering at its scheduled time, so that stacking events record all
+    /// of the occurrences they missed.
+    pub fn update_at(&mut self, now: LocalTime, holidays: &HolidayCalendar) {
+        self.update_with_vacations(now, &[], holidays);
+    }
+    /// Updates the event like `update_at`, except that occurrences on the days
+    /// of the vacations don't trigger. The schedule resumes from the end of
+    /// the vacation instead.
+    pub fn update_with_vacations(
+        &mut self,
+        now: LocalTime,
+        vacations: &[Vacation],
+        holidays: &HolidayCalendar,
+    ) {
+        // Paused events are left as they are until they're resumed
+        if self.is_paused() {
+            return;
+        }
 
-        // Early out if the event's not going to trigger
-        let next = match self.next_trigger_time() {
-            Some(t) => t,
-            None => return,
-        };
+        // Snoozed events wake up at their deadline, and then catch up as usual
+        if let StatusKind::Snoozed(until) = &self.1.status {
+            if now < until.0 {
+                return;
+            }
+            self.1.wake();
+        }
+
+        while let Some(next) = self.next_trigger_time(holidays) {
+            if next > now {
+                return;
+            }
+            let date = self.0.wall_clock(&next).date();
+            if let Some(vacation) = vacations.iter().find(|v| v.contains(date)) {
+                let vacation_end = self.0.instant(&vacation.ends().succ().and_hms(0, 0, 0));
+                self.1.schedule_from(Time(vacation_end));
+                continue;
+            }
+            // Stop if the interval does not move forward, eg. a zero time delta
+            let stuck = match self.1.prev_trigger_time() {
+                Some(prev) => next <= prev,
+                None => false,
+            };
+            self.1.trigger_occurrence_at(Time(next));
+            if stuck {
+                return;
+            }
+        }
 
-        if now >= next {
-            self.trigger_now();
+        // The event's not going to trigger, events that have run out of occurrences
+        // within their bounds or their interval are finished
+        let ends = self.0.is_bounded() || self.0.interval().has_end();
+        if ends && !self.is_triggered() && !self.is_finished() {
+            self.1.finish_at(Time(now));
         }
     }
     /// Returns true if the event moved from an untriggered start to a triggered
     /// state
-    pub fn trigger_at(&mut self, _t: LocalTime) -> bool {
-        unimplemented!()
+    pub fn trigger_at(&mut self, t: LocalTime) -> bool {
+        self.1.trigger_at(Time(t))
     }
-    /// Returns true if the event moved from an untriggered start to a triggered
-    /// state
-    pub fn trigger_now(&mut self) -> bool {
-        self.1.trigger_now()
+    pub fn complete_at(&mut self, t: LocalTime) -> bool {
+        self.1.complete_at(Time(t))
     }
-    pub fn complete_now(&mut self) -> bool {
-        self.1.complete_now()
+    /// Returns the upcoming occurrence that may be completed ahead of time at
+    /// `now`, ie. when `now` falls within the event's early-completion window
+    /// before the occurrence. Timer and one-shot events are always completable
+    /// and are not considered.
+    pub fn early_occurrence(
+        &self,
+        now: &LocalTime,
+        holidays: &HolidayCalendar,
+    ) -> Option<LocalTime> {
+        let window = self.0.early_window()?;
+        if let Interval::FromLastCompletion(_) | Interval::Once(_) = self.0.interval() {
+            return None;
+        }
+        if self.is_triggered() || self.is_snoozed() || self.1.skips() > 0 {
+            return None;
+        }
+        match self.next_trigger_time(holidays) {
+            Some(next) if next > *now && next - window.to_duration() <= *now => Some(next),
+            _ => None,
+        }
+    }
+    /// Returns how long the event has been overdue at `now`, ie. how far past
+    /// its grace period the earliest pending occurrence is. The grace period
+    /// defaults to the length of the interval. Returns None if the event is
+    /// not triggered or not overdue.
+    pub fn overdue_by(&self, now: &LocalTime) -> Option<Duration> {
+        if !self.is_triggered() {
+            return None;
+        }
+        let first = self.1.trigger_times().first()?;
+        let grace = match self.0.grace_period() {
+            Some(grace) => grace.to_duration(),
+            None => self.0.interval().to_duration_heuristic()?,
+        };
+        let overdue = now.signed_duration_since(first.0) - grace;
+        if overdue > Duration::zero() {
+            Some(overdue)
+        } else {
+            None
+        }
     }
     /// Returns None if the fraction cannot be evaluated
-    pub fn fraction_of_interval_remaining(&self, at_time: &LocalTime) -> Option<f64> {
+    pub fn fraction_of_interval_remaining(
+        &self,
+        at_time: &LocalTime,
+        holidays: &HolidayCalendar,
+    ) -> Option<f64> {
         let state = &self.1;
         let event = &self.0;
 
@@ -65,7 +169,7 @@ impl TrackedEvent {
             }
         }
 
-        match self.next_trigger_time() {
+        match self.next_trigger_time(holidays) {
             // Wait doesn't apply if the event is not going to trigger
             None => None,
             Some(next) => {
@@ -83,18 +187,59 @@ impl TrackedEvent {
         }
     }
     /// Returns the next time this event is going to trigger. Returns None if
-    /// currently triggered.
-    pub fn next_trigger_time(&self) -> Option<LocalTime> {
-        let interval = self.0.interval();
+    /// currently triggered, finished, paused or if the next time would fall
+    /// outside the event's end date or occurrence limit. Business days skip
+    /// `holidays`.
+    pub fn next_trigger_time(&self, holidays: &HolidayCalendar) -> Option<LocalTime> {
+        let event = &self.0;
         let state = &self.1;
 
-        // Does not stack -> does not re-trigger
-        if let StatusKind::Triggered = state.status {
-            if !self.0.stacks() {
+        match &state.status {
+            // Does not stack -> does not re-trigger
+            StatusKind::Triggered if !event.stacks() => return None,
+            StatusKind::Finished(_) => return None,
+            _ if state.is_paused() => return None,
+            // Snoozed events trigger again when the snooze ends
+            StatusKind::Snoozed(until) => return Some(until.0),
+            _ => {}
+        }
+        if let Some(max) = event.max_occurrences() {
+            if state.occurrences() >= max {
                 return None;
             }
         }
 
+        let next = self.scheduled_trigger_time(holidays)?;
+        match event.ends() {
+            Some(ends) if event.wall_clock(&next).date() > ends => None,
+            _ => Some(next),
+        }
+    }
+    /// Returns the next time of trigger by the event's interval and start date
+    fn scheduled_trigger_time(&self, holidays: &HolidayCalendar) -> Option<LocalTime> {
+        // Occurrences out of season are passed over by counting again from the start of
+        // the next season, give up if the interval never hits a season
+        let mut season_start = None;
+        for _ in 0..MAX_SEASON_JUMPS {
+            let next = self.scheduled_trigger_time_from(season_start, holidays)?;
+            let date = self.0.wall_clock(&next).date();
+            if !self.0.interval().is_recurring() || self.0.is_in_season(date) {
+                return Some(next);
+            }
+            season_start = Some(self.0.next_season_start(date)?);
+        }
+        None
+    }
+    /// Returns the next time of trigger by the event's interval, counting from
+    /// the event's start date or `season_start`, whichever is later
+    fn scheduled_trigger_time_from(
+        &self,
+        season_start: Option<NaiveDate>,
+        holidays: &HolidayCalendar,
+    ) -> Option<LocalTime> {
+        let interval = self.0.interval();
+        let state = &self.1;
+
         // Start counting the next time of trigger from the previous trigger time if
         // possible, otherwise, pick 1. time of registration, 2. time of skipping, time
         // of completion
@@ -110,87 +255,57 @@ impl TrackedEvent {
                 .0
             }
         };
-        match interval {
-            Interval::FromLastCompletion(delta) => Some(delta.apply_to(prev_trigger)),
-            Interval::Periodic(TimePeriod::Annual(AnnualDay { month, day }, time)) => {
-                let an_instance = LocalTime::from_utc(
-                    NaiveDate::from_ymd(prev_trigger.year(), *month, *day).and_time(*time),
-                    FixedOffset::east(0),
-                );
-
-                // If the constructed instance is before our time, move it one year forward and
-                // return
-                Some(if an_instance < prev_trigger {
-                    LocalTime::from_utc(
-                        NaiveDate::from_ymd(prev_trigger.year() + 1, *month, *day).and_time(*time),
-                        FixedOffset::east(0),
-                    )
-                } else {
-                    an_instance
-                })
-            }
-            Interval::Periodic(TimePeriod::MultiAnnual(_days)) => unimplemented!(),
-            Interval::Periodic(TimePeriod::Monthly(MonthlyDay { day }, time)) => {
-                let an_instance = LocalTime::from_utc(
-                    NaiveDate::from_ymd(prev_trigger.year(), prev_trigger.month(), *day)
-                        .and_time(*time),
-                    FixedOffset::east(0),
-                );
-
-                // If the constructed instance is before our time, move it one month forward and
-                // return
-                Some(if an_instance < prev_trigger {
-                    LocalTime::from_utc(
-                        NaiveDate::from_ymd(
-                            prev_trigger.year(),
-                            prev_trigger.month() % 12 + 1,
-                            *day,
-                        )
-                        .and_time(*time),
-                        FixedOffset::east(0),
-                    )
-                } else {
-                    an_instance
-                })
-            }
-            Interval::Periodic(TimePeriod::Weekly(weekday, time)) => {
-                let an_instance = LocalTime::from_utc(
-                    NaiveDate::from_isoywd(
-                        prev_trigger.year(),
-                        prev_trigger.iso_week().week(),
-                        *weekday,
-                    )
-                    .and_time(*time),
-                    FixedOffset::east(0),
-                );
-
-                // If the constructed instance is before our time, move it one week forward and
-                // return
-                Some(if an_instance < prev_trigger {
-                    an_instance + Duration::weeks(1)
-                } else {
-                    an_instance
-                })
+        // Occurrences skipped while the event is triggered are counted on from as well
+        let prev_trigger = match state.skipped() {
+            Some(skipped) => std::cmp::max(prev_trigger, skipped),
+            None => prev_trigger,
+        };
+        // Periodic instances are computed on the wall clock of the event's time zone,
+        // no instances are produced before the start date or the season
+        let start = std::cmp::max(self.0.starts(), season_start)
+            .map(|date| date.and_hms(0, 0, 0) - Duration::nanoseconds(1));
+        let prev = match start {
+            Some(start) => std::cmp::max(self.0.wall_clock(&prev_trigger), start),
+            None => self.0.wall_clock(&prev_trigger),
+        };
+        // Nothing triggers before the event was last resumed, timers restart then
+        let (prev_trigger, prev) = match state.resumed() {
+            Some(resumed) => (
+                std::cmp::max(prev_trigger, resumed),
+                std::cmp::max(prev, self.0.wall_clock(&resumed) - Duration::nanoseconds(1)),
+            ),
+            None => (prev_trigger, prev),
+        };
+        let next = match interval {
+            Interval::FromLastCompletion(delta) => {
+                let next = delta.apply_to(prev_trigger, &self.0, holidays);
+                return match start {
+                    Some(start) if self.0.wall_clock(&next) <= start => {
+                        Some(self.0.instant(&(start + Duration::nanoseconds(1))))
+                    }
+                    _ => Some(next),
+                };
             }
-            Interval::Periodic(TimePeriod::Daily(time)) => {
-                let an_instance = LocalTime::from_utc(
-                    NaiveDate::from_ymd(
-                        prev_trigger.year(),
-                        prev_trigger.month(),
-                        prev_trigger.day(),
-                    )
-                    .and_time(*time),
-                    FixedOffset::east(0),
-                );
-
-                // If the constructed instance is before our time, move it one day forward and
-                // return
-                Some(if an_instance < prev_trigger {
-                    an_instance + Duration::days(1)
-                } else {
-                    an_instance
-                })
+            // One-shot events trigger at their time even if it has passed already, or at the
+            // end of a pause or a vacation that covers it. One-shot events that were skipped
+            // don't trigger either.
+            Interval::Once(time) => match (state.occurrences(), &state.status) {
+                (0, StatusKind::Skip(_)) => None,
+                (0, _) => match state.resumed() {
+                    Some(resumed) => Some(std::cmp::max(*time, self.0.wall_clock(&resumed))),
+                    None => Some(*time),
+                },
+                _ => None,
+            },
+            // Rules are anchored to a fixed time so that completing late or jumping over a
+            // pause doesn't shift the recurrence
+            Interval::RRule(rule) => {
+                let anchor = state.registered().unwrap_or(prev_trigger);
+                rule.next_after(&prev, &self.0.wall_clock(&anchor))
             }
-        }
+            Interval::Cron(cron) => cron.next_after(&prev),
+            Interval::Periodic(period) => period.next_after(&prev, holidays),
+        };
+        next.map(|t| self.0.instant(&t))
     }
 }
@@ -0,0 +1,41 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A range of days during which no events trigger, both ends inclusive. The
+/// days are matched on the wall clock of each event's time zone.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Vacation {
+    starts: NaiveDate,
+    ends: NaiveDate,
+}
+
+impl Vacation {
+    /// Returns None if the vacation would end before it starts
+    pub fn new(starts: NaiveDate, ends: NaiveDate) -> Option<Vacation> {
+        match starts <= ends {
+            true => Some(Vacation { starts, ends }),
+            false => None,
+        }
+    }
+    pub fn starts(&self) -> NaiveDate {
+        self.starts
+    }
+    pub fn ends(&self) -> NaiveDate {
+        self.ends
+    }
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.starts <= date && date <= self.ends
+    }
+}
+
+impl fmt::Display for Vacation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} - {}",
+            self.starts.format("%-d.%-m.%Y"),
+            self.ends.format("%-d.%-m.%Y")
+        )
+    }
+}
@@ -29,6 +29,25 @@ fn main() {
     info!("Loading default config");
     let mut config = Config::load_default();
 
+    // Load the holidays that business-day schedules skip
+    let holidays = match &config.holidays {
+        None => datamodel::HolidayCalendar::default(),
+        Some(path) => {
+            info!("Loading holidays from: {}", path);
+            let path = shellexpand::full(path)
+                .map(|p| p.into_owned())
+                .unwrap_or_else(|_| path.clone());
+            match datamodel::HolidayCalendar::from_file(&path) {
+                Ok(calendar) => calendar,
+                Err(e) => {
+                    warn!("{}", e);
+                    println!("{}, business days are counted without holidays", e);
+                    datamodel::HolidayCalendar::default()
+                }
+            }
+        }
+    };
+
     // Try to open the last opened file from config cache
     info!("Looking for last opened path in config");
     let last_path = {
@@ -63,6 +82,6 @@ fn main() {
     };
 
     info!("User starts interaction with tracker");
-    let mut gui = TrackerCli::new(tracker);
+    let mut gui = TrackerCli::new(tracker.with_holidays(holidays));
     gui.interact_modal(&path);
 }
@@ -4,11 +4,29 @@ pub use std::convert::TryFrom;
 pub type LocalTime = DateTime<Local>;
 pub type UtcTime = DateTime<Utc>;
 
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Duration, Local, Utc};
 
 // TODO: make util.rs or something
-pub fn is_today(time: &LocalTime) -> bool {
-    let today = Local::now().date();
+pub fn is_today(time: &LocalTime, now: &LocalTime) -> bool {
+    let today = now.date();
     let date = time.date();
     today == date
 }
+
+/// Formats the time remaining until a deadline with its two most significant
+/// units, eg. "3d 4h" or "12m"
+pub fn format_countdown(remaining: Duration) -> String {
+    if remaining <= Duration::zero() {
+        return "now".to_string();
+    }
+    let (days, hours, minutes) = (
+        remaining.num_days(),
+        remaining.num_hours() % 24,
+        remaining.num_minutes() % 60,
+    );
+    match (days, hours) {
+        (0, 0) => format!("{}m", std::cmp::max(minutes, 1)),
+        (0, h) => format!("{}h {}m", h, minutes),
+        (d, h) => format!("{}d {}h", d, h),
+    }
+}
@@ -4,7 +4,9 @@ pub mod event_store;
 #[cfg(test)]
 mod test;
 
-use crate::datamodel::{EventData, Status};
+use crate::datamodel::{
+    outcomes, Clock, EventData, EventStats, HolidayCalendar, Status, SystemClock, Time, Vacation,
+};
 use crate::prelude::*;
 use crate::tracker::command::{Apply, CommandReceiver, FnApply};
 use dialoguer::Confirmation;
@@ -15,9 +17,17 @@ use event_store::{EventStore, TrackedEvent};
 
 use std::path::Path;
 
+/// Statistics of a tracker, see `Tracker::stats`
+pub struct TrackerStats {
+    pub events: Vec<(Uid, EventStats)>,
+    pub overall: EventStats,
+}
+
 pub struct Tracker {
     tracked_events: EventStore,
     undo_buffer: Vec<FnApply>,
+    clock: Box<dyn Clock>,
+    holidays: HolidayCalendar,
 }
 
 impl Tracker {
@@ -25,17 +35,48 @@ impl Tracker {
         Tracker {
             tracked_events,
             undo_buffer: Vec::new(),
+            clock: Box::new(SystemClock),
+            holidays: HolidayCalendar::default(),
         }
     }
     pub fn empty() -> Tracker {
+        Tracker::with_events(EventStore::new())
+    }
+    /// Replaces the clock that the tracker reads the current time from
+    pub fn with_clock<C>(self, clock: C) -> Tracker
+    where
+        C: Clock + 'static,
+    {
         Tracker {
-            tracked_events: EventStore::new(),
-            undo_buffer: Vec::new(),
+            clock: Box::new(clock),
+            ..self
         }
     }
+    /// Replaces the holidays that business-day schedules skip
+    pub fn with_holidays(self, holidays: HolidayCalendar) -> Tracker {
+        Tracker { holidays, ..self }
+    }
+    /// Returns the current time by the tracker's clock
+    pub fn now(&self) -> LocalTime {
+        self.clock.now()
+    }
+    /// Returns the holidays that business-day schedules skip
+    pub fn holidays(&self) -> &HolidayCalendar {
+        &self.holidays
+    }
     pub fn from_path<P>(path: P) -> Result<Tracker, LoadError>
     where
         P: AsRef<Path>,
+    {
+        Tracker::from_path_with_clock(path, SystemClock)
+    }
+    /// Loads a tracker that reads the current time from `clock`. The events
+    /// are as they were stored until they're brought up to date with
+    /// `update_events`, once the tracker is set up.
+    pub fn from_path_with_clock<P, C>(path: P, clock: C) -> Result<Tracker, LoadError>
+    where
+        P: AsRef<Path>,
+        C: Clock + 'static,
     {
         debug!(
             "Reading events for tracker from path: {}",
@@ -43,13 +84,14 @@ impl Tracker {
         );
         let events = EventStore::from_file(path);
         match events {
-            Ok(events) => Ok(Tracker::with_events(events)),
+            Ok(events) => Ok(Tracker::with_events(events).with_clock(clock)),
             Err(e) => Err(e),
         }
     }
 
     pub fn update_events(&mut self) {
-        self.tracked_events.update_events();
+        let now = self.now();
+        self.tracked_events.update_events(now, &self.holidays);
     }
     pub fn refresh_from_disk<P>(&mut self, path: P) -> Result<(), LoadError>
     where
@@ -62,6 +104,7 @@ impl Tracker {
                 return Err(e);
             }
         };
+        self.update_events();
         Ok(())
     }
     pub fn store_to_disk<P>(&self, path: P)
@@ -101,6 +144,43 @@ impl Tracker {
             .collect::<Vec<_>>()
     }
 
+    /// Returns the date ranges during which no events trigger
+    pub fn vacations(&self) -> &[Vacation] {
+        self.tracked_events.vacations()
+    }
+    pub fn add_vacation(&mut self, vacation: Vacation) {
+        self.tracked_events.add_vacation(vacation);
+    }
+    /// Removes a vacation, returns None if it was not found
+    pub fn remove_vacation(&mut self, vacation: &Vacation) -> Option<Vacation> {
+        self.tracked_events.remove_vacation(vacation)
+    }
+
+    /// Returns the statistics of each event and of all events together. The
+    /// overall streaks run over the occurrences of every event in order of
+    /// resolution.
+    pub fn stats(&self) -> TrackerStats {
+        let mut all_outcomes = vec![];
+        let mut all_pending = 0;
+        let events = self
+            .tracked_events
+            .iter()
+            .map(|(uid, event)| {
+                let (outcomes, pending) = outcomes(event.1.history());
+                let stats = EventStats::from_outcomes(&outcomes, pending);
+                all_outcomes.extend(outcomes);
+                all_pending += pending;
+                (*uid, stats)
+            })
+            .collect();
+        all_outcomes.sort_by_key(|outcome| outcome.time);
+
+        TrackerStats {
+            events,
+            overall: EventStats::from_outcomes(&all_outcomes, all_pending),
+        }
+    }
+
     pub fn undo(&mut self) {
         trace!("Undo starts");
 
@@ -116,7 +196,8 @@ impl Tracker {
     }
 
     pub fn add_event(&mut self, event: EventData) -> event_store::Uid {
-        self.add_event_with_status(event, Status::default())
+        let status = Status::from_time(Time(self.now()));
+        self.add_event_with_status(event, status)
     }
 
     // Returns None if an event was not found with id
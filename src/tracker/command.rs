@@ -1,7 +1,9 @@
 use super::error::CommandError;
 use super::event_store::Uid;
 use super::Tracker;
-use crate::datamodel::{EventData, Interval, TrackedEvent};
+use crate::datamodel::{
+    EventData, Interval, Status, Time, TimeDelta, TimeSpec, TrackedEvent, Vacation,
+};
 use crate::prelude::*;
 use crate::view::tracker_cli::{TrackerCli, ViewState};
 use dialoguer::Confirmation;
@@ -56,6 +58,18 @@ pub static COMMAND_KEYS: CommandKeys = {
             "set event as completed",
             Complete
         ),
+        cmd!(
+            "done <id> at <time>",
+            ["done"],
+            "set event as completed at a past time, eg. @yesterday 18:30",
+            Complete
+        ),
+        cmd!(
+            "all <id>",
+            ["all"],
+            "complete every pending occurrence of an event",
+            CompleteAll
+        ),
         cmd!(
             "create",
             ["create", "c"],
@@ -75,6 +89,48 @@ pub static COMMAND_KEYS: CommandKeys = {
             "manually trigger an event now",
             Trigger
         ),
+        cmd!(
+            "skip <id> [n]",
+            ["skip"],
+            "skip the next n occurrences of an event, 1 by default",
+            Skip
+        ),
+        cmd!(
+            "snooze <id> <duration>",
+            ["snooze", "z"],
+            "postpone a triggered event, eg. snooze 2 1h30m",
+            Snooze
+        ),
+        cmd!(
+            "pause <id>",
+            ["pause", "p"],
+            "pause an event until it's resumed",
+            Pause
+        ),
+        cmd!(
+            "resume <id>",
+            ["resume", "r"],
+            "resume a paused event, scheduling from now on",
+            Resume
+        ),
+        cmd!(
+            "vacation <from> <until>",
+            ["vacation"],
+            "set days on which no events trigger, eg. vacation 2020-07-01 2020-07-14",
+            Vacation
+        ),
+        cmd!(
+            "history <id>",
+            ["history", "hist"],
+            "show what has happened to an event",
+            History
+        ),
+        cmd!(
+            "stats",
+            ["stats"],
+            "show completion statistics and streaks",
+            Stats
+        ),
         cmd!(
             "show",
             ["show", "s"],
@@ -103,10 +159,18 @@ enum CommandInput {
     Remove,
     Alter,
     Trigger,
+    Skip,
+    Snooze,
+    Pause,
+    Resume,
+    Vacation,
+    History,
+    Stats,
     Show,
     Hide,
     Undo,
     Complete,
+    CompleteAll,
     Exit,
 }
 
@@ -179,7 +243,7 @@ impl Deref for CommandKeys {
 }
 
 /// Interpret user action as a command
-pub fn match_command(input: &str, id_to_uid: &[Uid]) -> Option<CommandKind> {
+pub fn match_command(input: &str, id_to_uid: &[Uid], now: &LocalTime) -> Option<CommandKind> {
     // Sanitize
     let input = input.trim();
 
@@ -200,7 +264,7 @@ pub fn match_command(input: &str, id_to_uid: &[Uid]) -> Option<CommandKind> {
                 Some(cmd_input) => match cmd_input {
                     // 'Create' launches the event creation wizard
                     CommandInput::Create => {
-                        let cmd = match crate::view::tracker_cli::create_event_interact() {
+                        let cmd = match crate::view::tracker_cli::create_event_interact(now) {
                             Some(cmd) => cmd,
                             None => return None,
                         };
@@ -220,16 +284,79 @@ pub fn match_command(input: &str, id_to_uid: &[Uid]) -> Option<CommandKind> {
 
                         // TODO: create a real interface for the alter command
                         println!("Hack: using 'create event' interface to replace the old event");
-                        let cmd = match crate::view::tracker_cli::create_event_interact() {
+                        let cmd = match crate::view::tracker_cli::create_event_interact(now) {
                             Some(cmd) => AlterCommand(uid, cmd.0),
                             None => return None,
                         };
                         Some(DataCommand(Box::new(cmd)))
                     }
                     CommandInput::Trigger => {
+                        let (tokens, at) = split_time_spec(tokens.skip(1).collect())?;
+                        id_token_to_uid_interact(&mut tokens.into_iter(), id_to_uid)
+                            .map(|uid| DataCommand(Box::new(TriggerCommand(uid, at))))
+                    }
+                    CommandInput::Skip => {
+                        let tokens = tokens.skip(1).collect::<Vec<&str>>();
+                        let uid = id_token_to_uid_interact(
+                            &mut tokens.iter().take(1).cloned(),
+                            id_to_uid,
+                        )?;
+                        match tokens.get(1).map(|count| count.parse::<u32>()) {
+                            None => Some(DataCommand(Box::new(SkipCommand(uid, 1)))),
+                            Some(Ok(count)) => Some(DataCommand(Box::new(SkipCommand(uid, count)))),
+                            Some(Err(_)) => {
+                                println!(
+                                    "Cannot parse a number of occurrences from '{}'",
+                                    tokens[1]
+                                );
+                                None
+                            }
+                        }
+                    }
+                    CommandInput::Snooze => {
+                        let tokens = tokens.skip(1).collect::<Vec<&str>>();
+                        let uid = id_token_to_uid_interact(
+                            &mut tokens.iter().take(1).cloned(),
+                            id_to_uid,
+                        )?;
+                        match tokens[1..].join("").parse::<TimeDelta>() {
+                            Ok(delta) => Some(DataCommand(Box::new(SnoozeCommand(uid, delta)))),
+                            Err(e) => {
+                                println!("{}", e);
+                                None
+                            }
+                        }
+                    }
+                    CommandInput::Pause => id_token_to_uid_interact(&mut tokens.skip(1), id_to_uid)
+                        .map(|uid| DataCommand(Box::new(PauseCommand(uid)))),
+                    CommandInput::Resume => {
                         id_token_to_uid_interact(&mut tokens.skip(1), id_to_uid)
-                            .map(|uid| DataCommand(Box::new(TriggerCommand(uid))))
+                            .map(|uid| DataCommand(Box::new(ResumeCommand(uid))))
                     }
+                    CommandInput::Vacation => {
+                        let dates = tokens
+                            .skip(1)
+                            .map(|token| token.parse::<TimeSpec>())
+                            .collect::<Result<Vec<TimeSpec>, _>>();
+                        match dates {
+                            Ok(dates) if dates.len() == 2 => Some(DataCommand(Box::new(
+                                VacationCommand(dates[0].clone(), dates[1].clone()),
+                            ))),
+                            Ok(_) => {
+                                println!("Give the first and the last day of the vacation");
+                                None
+                            }
+                            Err(e) => {
+                                println!("{}", e);
+                                None
+                            }
+                        }
+                    }
+                    CommandInput::History => {
+                        id_token_to_uid_interact(&mut tokens.skip(1), id_to_uid)
+                            .map(|uid| CliCommand(Box::new(HistoryCommand(uid))))
+                    }
+                    CommandInput::Stats => Some(CliCommand(Box::new(StatsCommand))),
                     CommandInput::Show => Some(CliCommand(Box::new(ShowCommand))),
                     CommandInput::Hide => Some(CliCommand(Box::new(HideCommand))),
                     CommandInput::Undo => Some(Undo),
@@ -238,8 +365,11 @@ pub fn match_command(input: &str, id_to_uid: &[Uid]) -> Option<CommandKind> {
                     //CommandInput::Refresh => Some(ReversibleCommand(Box::new(RefreshCommand))),
                     // Set item status as 'completed'
                     CommandInput::Complete => {
+                        let (tokens, at) = split_time_spec(tokens.collect())?;
+
                         // Try to match a number ID from all elements
                         let uids = tokens
+                            .into_iter()
                             .filter_map(|token| {
                                 token.parse::<usize>().ok().and_then(|id| id_to_uid.get(id))
                             })
@@ -247,7 +377,20 @@ pub fn match_command(input: &str, id_to_uid: &[Uid]) -> Option<CommandKind> {
                             .collect::<Vec<Uid>>();
 
                         // Set up the command
-                        Some(DataCommand(Box::new(CompleteCommand(uids))))
+                        Some(DataCommand(Box::new(CompleteCommand(uids, at))))
+                    }
+                    // Set every pending occurrence of the items as 'completed'
+                    CommandInput::CompleteAll => {
+                        let (tokens, at) = split_time_spec(tokens.skip(1).collect())?;
+                        let uids = tokens
+                            .into_iter()
+                            .filter_map(|token| {
+                                token.parse::<usize>().ok().and_then(|id| id_to_uid.get(id))
+                            })
+                            .cloned()
+                            .collect::<Vec<Uid>>();
+
+                        Some(DataCommand(Box::new(CompleteAllCommand(uids, at))))
                     }
                 },
                 None => unreachable!(),
@@ -288,8 +431,12 @@ pub trait Apply: Display + std::fmt::Debug {
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RemoveCommand(pub Vec<Uid>);
 
+/// Completes the events, at the given time if set
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompleteCommand(pub Vec<Uid>, pub Option<TimeSpec>);
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct CompleteCommand(pub Vec<Uid>);
+pub struct CompleteAllCommand(pub Vec<Uid>, pub Option<TimeSpec>);
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub struct ShowCommand;
@@ -394,10 +541,14 @@ impl_cmd!(AlterCommand(Uid, EventData), |self, target| {
     }
 });
 
-impl_cmd!(TriggerCommand(Uid), |self, target| {
+impl_cmd!(
+    /// Triggers the event, at the given time if set
+    TriggerCommand(Uid, Option<TimeSpec>),
+    |self, target| {
     match target {
         CommandReceiver::Tracker(tracker) => {
             let uid = self.0;
+            let now = Time(resolve_time(tracker, &self.1)?);
 
             // Op
             let old_state = match tracker.event_mut(uid) {
@@ -406,8 +557,14 @@ impl_cmd!(TriggerCommand(Uid), |self, target| {
                     return Err(CommandError::EventNotFound(uid));
                 }
                 Some(TrackedEvent(_, state)) => {
+                    // Triggers are kept in order, a backdated one can't precede the latest
+                    if let Some(prev) = state.prev_trigger_time() {
+                        if now.0 < prev {
+                            return Err(CommandError::BeforeTrigger(uid, now.0, prev));
+                        }
+                    }
                     let old_state = state.clone();
-                    state.trigger_now();
+                    state.trigger_at(now);
                     old_state
                 }
             };
@@ -431,6 +588,232 @@ impl_cmd!(TriggerCommand(Uid), |self, target| {
     }
 });
 
+impl_cmd!(
+    /// Skips the next occurrences of the event, the current occurrences of a
+    /// triggered event are left as they are
+    SkipCommand(Uid, u32),
+    |self, target| {
+    match target {
+        CommandReceiver::Tracker(tracker) => {
+            let uid = self.0;
+
+            // Op
+            let old_state = match tracker.event_mut(uid) {
+                None => {
+                    warn!("SkipCommand failed because the event being skipped did not exist");
+                    return Err(CommandError::EventNotFound(uid));
+                }
+                Some(TrackedEvent(_, state)) => {
+                    let old_state = state.clone();
+                    state.skip_next(self.1);
+                    old_state
+                }
+            };
+
+            // Undo
+            Ok(Some(Box::new(move |tracker: &mut Tracker| {
+                match tracker.event_mut(uid) {
+                    None => warn!(
+                        "Undo failed for SkipCommand with uid {} because uid did not exist",
+                        uid
+                    ),
+                    Some(TrackedEvent(_, ref mut state)) => {
+                        *state = old_state;
+                    }
+                }
+            })))
+        }
+        CommandReceiver::TrackerCli(_) => {
+            Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+        }
+    }
+});
+
+impl_cmd!(
+    /// Pauses the event, it doesn't trigger until resumed
+    PauseCommand(Uid),
+    |self, target| {
+    match target {
+        CommandReceiver::Tracker(tracker) => {
+            let uid = self.0;
+            let now = Time(tracker.now());
+
+            // Op
+            let old_state = match tracker.event_mut(uid) {
+                None => {
+                    warn!("PauseCommand failed because the event being paused did not exist");
+                    return Err(CommandError::EventNotFound(uid));
+                }
+                Some(TrackedEvent(_, state)) => {
+                    let old_state = state.clone();
+                    if !state.pause_at(now) {
+                        return Err(CommandError::AlreadyPaused(uid));
+                    }
+                    old_state
+                }
+            };
+
+            // Undo
+            Ok(Some(Box::new(move |tracker: &mut Tracker| {
+                match tracker.event_mut(uid) {
+                    None => warn!(
+                        "Undo failed for PauseCommand with uid {} because uid did not exist",
+                        uid
+                    ),
+                    Some(TrackedEvent(_, ref mut state)) => {
+                        *state = old_state;
+                    }
+                }
+            })))
+        }
+        CommandReceiver::TrackerCli(_) => {
+            Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+        }
+    }
+});
+
+impl_cmd!(
+    /// Resumes a paused event, the schedule continues from the time of resuming
+    ResumeCommand(Uid),
+    |self, target| {
+    match target {
+        CommandReceiver::Tracker(tracker) => {
+            let uid = self.0;
+            let now = Time(tracker.now());
+
+            // Op
+            let old_state = match tracker.event_mut(uid) {
+                None => {
+                    warn!("ResumeCommand failed because the event being resumed did not exist");
+                    return Err(CommandError::EventNotFound(uid));
+                }
+                Some(TrackedEvent(_, state)) => {
+                    let old_state = state.clone();
+                    if !state.resume_at(now) {
+                        return Err(CommandError::NotPaused(uid));
+                    }
+                    old_state
+                }
+            };
+
+            // Undo
+            Ok(Some(Box::new(move |tracker: &mut Tracker| {
+                match tracker.event_mut(uid) {
+                    None => warn!(
+                        "Undo failed for ResumeCommand with uid {} because uid did not exist",
+                        uid
+                    ),
+                    Some(TrackedEvent(_, ref mut state)) => {
+                        *state = old_state;
+                    }
+                }
+            })))
+        }
+        CommandReceiver::TrackerCli(_) => {
+            Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+        }
+    }
+});
+
+impl_cmd!(
+    /// Adds a vacation from the first to the last given day during which no
+    /// events trigger
+    VacationCommand(TimeSpec, TimeSpec),
+    |self, target| {
+    match target {
+        CommandReceiver::Tracker(tracker) => {
+            let now = tracker.now();
+            let (starts, ends) = (
+                self.0.resolve(&now).date().naive_local(),
+                self.1.resolve(&now).date().naive_local(),
+            );
+            let vacation = match Vacation::new(starts, ends) {
+                Some(vacation) => vacation,
+                None => return Err(CommandError::EndsBeforeStart(starts, ends)),
+            };
+
+            // Op
+            tracker.add_vacation(vacation.clone());
+
+            // Undo
+            Ok(Some(Box::new(move |tracker: &mut Tracker| {
+                if tracker.remove_vacation(&vacation).is_none() {
+                    warn!("Undo failed for VacationCommand because the vacation did not exist");
+                }
+            })))
+        }
+        CommandReceiver::TrackerCli(_) => {
+            Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+        }
+    }
+});
+
+impl_cmd!(SnoozeCommand(Uid, TimeDelta), |self, target| {
+    match target {
+        CommandReceiver::Tracker(tracker) => {
+            let uid = self.0;
+            let now = tracker.now();
+            let until = tracker
+                .event(uid)
+                .map(|TrackedEvent(event, _)| self.1.apply_to(now, event, tracker.holidays()));
+
+            // Op
+            let old_state = match (tracker.event_mut(uid), until) {
+                (None, _) | (_, None) => {
+                    warn!("SnoozeCommand failed because the event being snoozed did not exist");
+                    return Err(CommandError::EventNotFound(uid));
+                }
+                (Some(TrackedEvent(_, state)), Some(until)) => {
+                    let old_state = state.clone();
+                    if !state.snooze_until(Time(until)) {
+                        return Err(CommandError::NotTriggered(uid));
+                    }
+                    old_state
+                }
+            };
+
+            // Undo
+            Ok(Some(Box::new(move |tracker: &mut Tracker| {
+                match tracker.event_mut(uid) {
+                    None => warn!(
+                        "Undo failed for SnoozeCommand with uid {} because uid did not exist",
+                        uid
+                    ),
+                    Some(TrackedEvent(_, ref mut state)) => {
+                        *state = old_state;
+                    }
+                }
+            })))
+        }
+        CommandReceiver::TrackerCli(_) => {
+            Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+        }
+    }
+});
+
+impl_cmd!(HistoryCommand(Uid), |self, target| {
+    match target {
+        CommandReceiver::TrackerCli(cli) => match cli.tracker.event(self.0) {
+            None => Err(CommandError::EventNotFound(self.0)),
+            Some(event) => {
+                cli.print_history(event);
+                Ok(None)
+            }
+        },
+        CommandReceiver::Tracker(_) => Err(CommandError::InvalidReceiver(format!("{:?}", target))),
+    }
+});
+
+impl_cmd!(StatsCommand, |self, target| {
+    match target {
+        CommandReceiver::TrackerCli(cli) => {
+            cli.print_stats();
+            Ok(None)
+        }
+        CommandReceiver::Tracker(_) => Err(CommandError::InvalidReceiver(format!("{:?}", target))),
+    }
+});
+
 impl Display for CommandKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -485,58 +868,137 @@ impl Apply for RemoveCommand {
 
 impl Display for CompleteCommand {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "cmd-complete({:?})", &self.0)
+        write!(f, "cmd-complete({:?}, {:?})", &self.0, &self.1)
     }
 }
 
 impl Apply for CompleteCommand {
     fn apply(&self, target: CommandReceiver) -> CommandResult {
         match target {
-            CommandReceiver::Tracker(tracker) => {
-                let uids = &self.0;
+            CommandReceiver::Tracker(tracker) => complete_events(tracker, &self.0, &self.1, false),
+            CommandReceiver::TrackerCli(_) => {
+                Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+            }
+        }
+    }
+}
 
-                let mut states = Vec::with_capacity(uids.len());
-                for &uid in uids {
-                    // Op
-                    let old_state = match tracker.event_mut(uid) {
-                        Some(TrackedEvent(ev, state)) => {
-                            let old_state = state.clone();
-                            match ev.interval() {
-                                // If event is timespan-based, set it complete, post-poning next
-                                // triggering
-                                Interval::FromLastCompletion(_) => {
-                                    state.complete_now();
-                                }
-                                // If event is periodic, set it as skipped, canceling the next
-                                // triggering
-                                Interval::Periodic(_) => {
-                                    state.skip_now();
-                                }
-                            }
-                            old_state
-                        }
-                        None => return Err(CommandError::EventNotFound(uid)),
-                    };
-                    states.push((uid, old_state));
-                }
+impl Display for CompleteAllCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cmd-complete-all({:?}, {:?})", &self.0, &self.1)
+    }
+}
 
-                // Undo
-                Ok(Some(Box::new(move |tracker| {
-                    for (uid, old_state) in states {
-                        match tracker.event_mut(uid) {
-                            None => warn!(
-                            "Undo failed for CompleteCommand with uid {} because uid did not exist",
-                            uid
-                        ),
-                            Some(TrackedEvent(_, state)) => {
-                                *state = old_state;
-                            }
+impl Apply for CompleteAllCommand {
+    fn apply(&self, target: CommandReceiver) -> CommandResult {
+        match target {
+            CommandReceiver::Tracker(tracker) => complete_events(tracker, &self.0, &self.1, true),
+            CommandReceiver::TrackerCli(_) => {
+                Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+            }
+        }
+    }
+}
+
+/// Completes the events, or the earliest pending occurrence of each stacking
+/// event unless `all_occurrences` is set
+fn complete_events(
+    tracker: &mut Tracker,
+    uids: &[Uid],
+    at: &Option<TimeSpec>,
+    all_occurrences: bool,
+) -> CommandResult {
+    let now = resolve_time(tracker, at)?;
+    let mut states = Vec::with_capacity(uids.len());
+    for &uid in uids {
+        // Op
+        let early = tracker
+            .event(uid)
+            .and_then(|event| event.early_occurrence(&now, tracker.holidays()));
+        let old_state = match tracker.event_mut(uid) {
+            Some(TrackedEvent(ev, state)) => {
+                let old_state = state.clone();
+                // Finished events have nothing left to complete
+                if state.is_finished() {
+                    restore_states(tracker, states);
+                    return Err(CommandError::AlreadyFinished(uid));
+                }
+                // A completion backdated before the occurrences it completes would let them
+                // trigger again
+                let one_occurrence = ev.stacks() && !all_occurrences;
+                let completed = match one_occurrence {
+                    true => state.trigger_times().first(),
+                    false => state.trigger_times().last(),
+                };
+                if let Some(triggered) = completed.map(|t| t.0) {
+                    if now < triggered {
+                        restore_states(tracker, states);
+                        return Err(CommandError::BeforeTrigger(uid, now, triggered));
+                    }
+                }
+                // Completing within the early window satisfies the upcoming occurrence
+                if let Some(occurrence) = early {
+                    state.complete_early_at(Time(now), Time(occurrence));
+                } else {
+                    match ev.interval() {
+                        // One-shot events are done for good once completed, even before they
+                        // trigger
+                        Interval::Once(_) => {
+                            state.complete_at(Time(now));
+                            state.finish_at(Time(now));
+                        }
+                        // Stacked occurrences are completed one at a time
+                        _ if ev.stacks()
+                            && (state.is_triggered() || state.is_snoozed())
+                            && !all_occurrences =>
+                        {
+                            state.complete_one_at(Time(now));
+                        }
+                        // If event is timespan-based, set it complete, post-poning next
+                        // triggering
+                        Interval::FromLastCompletion(_) => {
+                            state.complete_at(Time(now));
+                        }
+                        // If periodic event is triggered, complete the triggered occurrence,
+                        // leaving the next one be
+                        _ if state.is_triggered() || state.is_snoozed() => {
+                            state.complete_at(Time(now));
+                        }
+                        // A periodic event has no current occurrence to complete, upcoming
+                        // occurrences are skipped with the skip command instead
+                        Interval::Periodic(_) | Interval::RRule(_) | Interval::Cron(_) => {
+                            restore_states(tracker, states);
+                            return Err(CommandError::NotTriggered(uid));
                         }
                     }
-                })))
+                }
+                old_state
             }
-            CommandReceiver::TrackerCli(_) => {
-                Err(CommandError::InvalidReceiver(format!("{:?}", target)))
+            None => {
+                restore_states(tracker, states);
+                return Err(CommandError::EventNotFound(uid));
+            }
+        };
+        states.push((uid, old_state));
+    }
+
+    // Undo
+    Ok(Some(Box::new(move |tracker| {
+        restore_states(tracker, states)
+    })))
+}
+
+/// Sets the events back to the given states, either to undo a command or to
+/// roll back a command that failed midway
+fn restore_states(tracker: &mut Tracker, states: Vec<(Uid, Status)>) {
+    for (uid, old_state) in states {
+        match tracker.event_mut(uid) {
+            None => warn!(
+                "Restoring the state of uid {} failed because uid did not exist",
+                uid
+            ),
+            Some(TrackedEvent(_, state)) => {
+                *state = old_state;
             }
         }
     }
@@ -620,6 +1082,46 @@ where
     }
 }
 
+/// Splits the time given with "at <time>" or "@<time>" from the end of the
+/// tokens. Returns None if the time cannot be parsed.
+fn split_time_spec(tokens: Vec<&str>) -> Option<(Vec<&str>, Option<TimeSpec>)> {
+    let at_idx = match tokens
+        .iter()
+        .position(|&token| token == "at" || token.starts_with('@'))
+    {
+        Some(idx) => idx,
+        None => return Some((tokens, None)),
+    };
+    let mut spec_tokens = tokens[at_idx..].to_vec();
+    match spec_tokens[0] {
+        "at" => {
+            spec_tokens.remove(0);
+        }
+        token => spec_tokens[0] = &token[1..],
+    }
+
+    match spec_tokens.join(" ").parse::<TimeSpec>() {
+        Ok(spec) => Some((tokens[..at_idx].to_vec(), Some(spec))),
+        Err(e) => {
+            println!("{}", e);
+            None
+        }
+    }
+}
+
+/// Returns the time the spec refers to, or the current time if there is no
+/// spec. Times in the future are rejected.
+fn resolve_time(tracker: &Tracker, at: &Option<TimeSpec>) -> Result<LocalTime, CommandError> {
+    let now = tracker.now();
+    match at {
+        None => Ok(now),
+        Some(spec) => match spec.resolve(&now) {
+            t if t > now => Err(CommandError::TimeInFuture(t)),
+            t => Ok(t),
+        },
+    }
+}
+
 /// Returns the mapped UID based on the UI Tracker ID received as input
 fn id_token_to_uid_interact<'i, I>(input: &'i mut I, id_to_uid: &[Uid]) -> Option<Uid>
 where
@@ -1,4 +1,6 @@
 use super::event_store::Uid as EventUid;
+use crate::prelude::*;
+use chrono::NaiveDate;
 use std::error;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
@@ -7,6 +9,14 @@ use std::fmt::{Debug, Display, Formatter};
 pub enum CommandError {
     EventNotFound(EventUid),
     InvalidReceiver(String),
+    TimeInFuture(LocalTime),
+    // Parameters: event, time given, time the event triggered at
+    BeforeTrigger(EventUid, LocalTime, LocalTime),
+    NotTriggered(EventUid),
+    AlreadyFinished(EventUid),
+    AlreadyPaused(EventUid),
+    NotPaused(EventUid),
+    EndsBeforeStart(NaiveDate, NaiveDate),
 }
 
 #[derive(Debug)]
@@ -116,6 +126,38 @@ impl error::Error for CommandError {
 
 impl Display for CommandError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "cannot apply command")
+        match self {
+            CommandError::TimeInFuture(t) => write!(
+                f,
+                "cannot apply command, {} is in the future",
+                t.format("%-d.%-m.%Y %H:%M")
+            ),
+            CommandError::BeforeTrigger(uid, t, triggered) => write!(
+                f,
+                "cannot apply command, {} is before event {} triggered at {}",
+                t.format("%-d.%-m.%Y %H:%M"),
+                uid,
+                triggered.format("%-d.%-m.%Y %H:%M")
+            ),
+            CommandError::NotTriggered(uid) => {
+                write!(f, "cannot apply command, event {} is not triggered", uid)
+            }
+            CommandError::AlreadyFinished(uid) => {
+                write!(f, "cannot apply command, event {} is finished already", uid)
+            }
+            CommandError::AlreadyPaused(uid) => {
+                write!(f, "cannot apply command, event {} is paused already", uid)
+            }
+            CommandError::NotPaused(uid) => {
+                write!(f, "cannot apply command, event {} is not paused", uid)
+            }
+            CommandError::EndsBeforeStart(starts, ends) => write!(
+                f,
+                "cannot apply command, {} is before {}",
+                ends.format("%-d.%-m.%Y"),
+                starts.format("%-d.%-m.%Y")
+            ),
+            _ => write!(f, "cannot apply command"),
+        }
     }
 }
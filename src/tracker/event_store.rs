@@ -1,4 +1,5 @@
 use super::error::{ItemAlreadyExistsError, LoadError, NotFoundError, StoreError};
+use crate::datamodel::{HolidayCalendar, Vacation};
 pub use crate::datamodel::{TrackedEvent, Uid};
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -9,16 +10,24 @@ use std::io::{Read, Write};
 use std::path::Path;
 
 #[derive(Serialize, Deserialize, Debug)]
-#[serde(transparent)]
-pub struct EventStore(BTreeMap<Uid, TrackedEvent>);
+pub struct EventStore {
+    events: BTreeMap<Uid, TrackedEvent>,
+    /// Date ranges during which no events trigger
+    #[serde(default)]
+    vacations: Vec<Vacation>,
+}
 
 impl EventStore {
     /// Returns a new, empty event store
     pub fn new() -> EventStore {
-        EventStore(BTreeMap::new())
+        EventStore {
+            events: BTreeMap::new(),
+            vacations: vec![],
+        }
     }
 
-    /// Returns an event store from a YAML file containing a valid event store
+    /// Returns an event store from a YAML file containing a valid event store.
+    /// The events are as they were stored, see `update_events`.
     pub fn from_file<P>(path: P) -> Result<EventStore, LoadError>
     where
         P: AsRef<Path>,
@@ -33,13 +42,22 @@ impl EventStore {
                     return Ok(EventStore::new());
                 }
 
-                // Try load tracker from file
-                let events = serde_yaml::from_str::<EventStore>(&contents);
-                match events {
-                    Ok(mut events) => {
-                        events.update_events();
-                        Ok(events)
+                // Try load tracker from file, files from before vacations were stored contain
+                // just the events
+                let events_key = serde_yaml::Value::String("events".to_string());
+                let events = match serde_yaml::from_str::<serde_yaml::Value>(&contents) {
+                    Ok(serde_yaml::Value::Mapping(map)) if !map.contains_key(&events_key) => {
+                        serde_yaml::from_str::<BTreeMap<Uid, TrackedEvent>>(&contents).map(
+                            |events| EventStore {
+                                events,
+                                vacations: vec![],
+                            },
+                        )
                     }
+                    _ => serde_yaml::from_str::<EventStore>(&contents),
+                };
+                match events {
+                    Ok(events) => Ok(events),
                     Err(e) => Err(LoadError::FileContentsMalformed(
                         Box::new(e),
                         path.as_ref().to_string_lossy().to_string(),
@@ -90,22 +108,37 @@ impl EventStore {
     /// Returns the stored events as an ordered map (inner type)
     #[allow(dead_code)]
     pub fn events_by_uid(&self) -> &BTreeMap<Uid, TrackedEvent> {
-        &self.0
+        &self.events
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&Uid, &TrackedEvent)> {
-        self.0.iter()
+        self.events.iter()
+    }
+
+    pub fn vacations(&self) -> &[Vacation] {
+        &self.vacations
+    }
+    pub fn add_vacation(&mut self, vacation: Vacation) {
+        self.vacations.push(vacation);
+    }
+    /// Removes and returns a vacation that equals `vacation`, if found
+    pub fn remove_vacation(&mut self, vacation: &Vacation) -> Option<Vacation> {
+        let idx = self.vacations.iter().rposition(|v| v == vacation)?;
+        Some(self.vacations.remove(idx))
     }
 
-    pub fn update_events(&mut self) {
-        for tracked_event in self.0.values_mut() {
-            tracked_event.update();
+    /// Updates the events as they should be at time `now`, catching up on all
+    /// of the occurrences since the events were last updated except for the
+    /// ones during vacations. Business days skip `holidays`.
+    pub fn update_events(&mut self, now: LocalTime, holidays: &HolidayCalendar) {
+        for tracked_event in self.events.values_mut() {
+            tracked_event.update_with_vacations(now, &self.vacations, holidays);
         }
     }
 
     /// Resolves the next free UID based on the events that currently exist
     pub fn next_free_uid(&self) -> Uid {
-        match self.0.iter().map(|(&uid, _)| uid).max() {
+        match self.events.iter().map(|(&uid, _)| uid).max() {
             // No events? Return zero
             None => Uid(0),
             // Return the highest event UID + 1
@@ -122,7 +155,7 @@ impl EventStore {
         uid: Uid,
         event: TrackedEvent,
     ) -> Result<(), ItemAlreadyExistsError<Uid, TrackedEvent>> {
-        match self.0.insert(uid, event.clone()) {
+        match self.events.insert(uid, event.clone()) {
             None => Ok(()),
             Some(te) => Err(ItemAlreadyExistsError(uid, te, event)),
         }
@@ -133,7 +166,7 @@ impl EventStore {
     /// If the data structure did not have an item with this UID, NotFoundError
     /// is returned
     pub fn remove(&mut self, uid: Uid) -> Result<TrackedEvent, NotFoundError<Uid>> {
-        match self.0.remove(&uid) {
+        match self.events.remove(&uid) {
             Some(te) => Ok(te),
             None => Err(NotFoundError(uid)),
         }
@@ -144,14 +177,14 @@ impl EventStore {
     /// If the data structure did not have an item with this UID, NotFoundError
     /// is returned
     pub fn get_mut(&mut self, uid: Uid) -> Result<&mut TrackedEvent, NotFoundError<Uid>> {
-        match self.0.get_mut(&uid) {
+        match self.events.get_mut(&uid) {
             Some(te) => Ok(te),
             None => Err(NotFoundError(uid)),
         }
     }
     /// Returns a & to a stored event
     pub fn get(&self, uid: Uid) -> Result<&TrackedEvent, NotFoundError<Uid>> {
-        match self.0.get(&uid) {
+        match self.events.get(&uid) {
             Some(te) => Ok(te),
             None => Err(NotFoundError(uid)),
         }
@@ -1,14 +1,24 @@
 use super::*;
 use crate::datamodel::*;
 use crate::view::tracker_cli::TrackerCli;
-use chrono::{DateTime, Datelike, NaiveTime};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::{Europe::Helsinki, UTC};
 use lazy_static::lazy_static;
+use std::str::FromStr;
+use test_case::test_case;
 
 lazy_static! {
     static ref TEST_EVENT: EventData = EventData::new(
         Interval::FromLastCompletion(TimeDelta::Hm(0, 1)),
         "Test EventData".to_string(),
     );
+    static ref NO_HOLIDAYS: HolidayCalendar = HolidayCalendar::default();
+}
+
+/// Returns a path in the temporary directory that is unique to this test
+/// process, so that concurrent test runs don't share files
+fn temp_file_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("mtracker_{}_{}.yaml", name, std::process::id()))
 }
 
 #[test]
@@ -50,8 +60,9 @@ fn multiset_done() {
             .collect::<Vec<Uid>>();
 
         // Set events as triggered
+        let now = tracker.now();
         evs.into_iter().for_each(|uid| {
-            tracker.event_mut(uid).unwrap().trigger_now();
+            tracker.event_mut(uid).unwrap().trigger_at(now);
         });
 
         tracker.events()
@@ -135,6 +146,7 @@ fn complete() {
     let mut tracker = Tracker::empty();
 
     let handle = tracker.add_event(TEST_EVENT.clone());
+    let now = tracker.now();
 
     // Verify that the event is accessible with its handle
     let event = tracker.event_mut(handle).unwrap();
@@ -146,7 +158,7 @@ fn complete() {
     }
 
     // Trigger the event
-    event.trigger_now();
+    event.trigger_at(now);
 
     // Verify it's triggered
     assert!(tracker.event_mut(handle).unwrap().is_triggered());
@@ -154,7 +166,7 @@ fn complete() {
     let event = tracker.event_mut(handle).unwrap();
 
     // Complete the event handle
-    event.complete_now();
+    event.complete_at(now);
 
     // Verify it's completed
     assert!(tracker.event_mut(handle).unwrap().is_completed());
@@ -169,7 +181,8 @@ fn month_end_triggers_next_day() {
             NaiveTime::parse_from_str("15:00", "%H:%M").unwrap(),
         )),
         "Daily event".to_string(),
-    );
+    )
+    .with_timezone(UTC);
 
     let handle = tracker.add_event_with_status(
         event,
@@ -184,9 +197,1951 @@ fn month_end_triggers_next_day() {
     let trigger_date = tracker
         .event(handle)
         .unwrap()
-        .next_trigger_time()
+        .next_trigger_time(&NO_HOLIDAYS)
         .unwrap()
-        .date()
-        .naive_local();
+        .naive_utc()
+        .date();
     assert!(trigger_date.month() == 2 && trigger_date.day() == 1);
 }
+
+#[test]
+fn multi_annual_triggers_on_soonest_date() {
+    let mut tracker = Tracker::empty();
+
+    let event = EventData::new(
+        Interval::Periodic(TimePeriod::MultiAnnual(vec![
+            (
+                AnnualDay {
+                    month: 2,
+                    day: 15,
+                    overflow: DayOverflow::Clamp,
+                },
+                NaiveTime::parse_from_str("09:00", "%H:%M").unwrap(),
+            ),
+            (
+                AnnualDay {
+                    month: 5,
+                    day: 15,
+                    overflow: DayOverflow::Clamp,
+                },
+                NaiveTime::parse_from_str("10:00", "%H:%M").unwrap(),
+            ),
+            (
+                AnnualDay {
+                    month: 11,
+                    day: 15,
+                    overflow: DayOverflow::Clamp,
+                },
+                NaiveTime::parse_from_str("11:00", "%H:%M").unwrap(),
+            ),
+        ])),
+        "Multi-annual event".to_string(),
+    )
+    .with_timezone(UTC);
+
+    // Registered after the first date of the year
+    let handle = tracker.add_event_with_status(
+        event.clone(),
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339("2020-03-01T12:00:00+00:00")
+                .unwrap()
+                .into(),
+        )),
+    );
+
+    // Verify event triggers next on May 15th, at its own time
+    let trigger_time = tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap();
+    let trigger_date = trigger_time.naive_utc().date();
+    assert_eq!((trigger_date.month(), trigger_date.day()), (5, 15));
+    assert_eq!(
+        trigger_time.naive_utc().time(),
+        NaiveTime::from_hms(10, 0, 0)
+    );
+
+    // Registered after the last date of the year
+    let handle = tracker.add_event_with_status(
+        event,
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339("2020-12-01T12:00:00+00:00")
+                .unwrap()
+                .into(),
+        )),
+    );
+
+    // Verify event wraps around to the first date of the next year
+    let trigger_date = tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap()
+        .naive_utc()
+        .date();
+    assert_eq!(
+        (
+            trigger_date.year(),
+            trigger_date.month(),
+            trigger_date.day()
+        ),
+        (2021, 2, 15)
+    );
+}
+
+#[test]
+fn invalid_cron_is_rejected_on_load() {
+    let path = temp_file_path("invalid_cron_test");
+    std::fs::write(
+        &path,
+        "---
+0:
+  - text: Invalid cron event
+    interval:
+      Cron: \"61 * * * *\"
+    stacks: false
+  - trigger_times: []
+    status:
+      Dormant: \"2020-01-01T00:00:00+00:00\"
+",
+    )
+    .unwrap();
+
+    match event_store::EventStore::from_file(&path) {
+        Err(LoadError::FileContentsMalformed(e, _, _)) => {
+            assert!(e.to_string().contains("invalid cron expression"))
+        }
+        Err(e) => panic!("unexpected error: {:?}", e),
+        Ok(_) => panic!("invalid cron expression was accepted"),
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Registers an event with the recurrence rule on Jan. 1st 2020, completes its
+/// first occurrence at `completed` and returns the next trigger time
+fn rrule_next_after_completion(rule: &str, completed: &str) -> String {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(Interval::RRule(rule.parse().unwrap()), "Rule".to_string())
+            .with_timezone(UTC),
+    );
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_triggered());
+
+    clock.set(instant(completed));
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+    tracker
+        .event(uid)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap()
+        .with_timezone(&Utc)
+        .to_rfc3339()
+}
+
+#[test_case("FREQ=WEEKLY;INTERVAL=2;BYHOUR=9;BYMINUTE=0", "2020-01-08T10:00:00+00:00" => "2020-01-15T09:00:00+00:00" ; "biweekly completed a week late")]
+#[test_case("FREQ=MONTHLY;BYHOUR=9;BYMINUTE=0", "2020-01-05T10:00:00+00:00" => "2020-02-01T09:00:00+00:00" ; "monthly completed on the 5th")]
+fn rrule_does_not_drift_when_completed_late(rule: &str, completed: &str) -> String {
+    rrule_next_after_completion(rule, completed)
+}
+
+/// Returns the next trigger time of a daily event in Helsinki registered at
+/// `registered`
+fn next_daily_in_helsinki(time: &str, registered: &str) -> DateTime<Utc> {
+    let mut tracker = Tracker::empty();
+    let event = EventData::new(
+        Interval::Periodic(TimePeriod::Daily(
+            NaiveTime::parse_from_str(time, "%H:%M").unwrap(),
+        )),
+        "Daily event".to_string(),
+    )
+    .with_timezone(Helsinki);
+    let handle = tracker.add_event_with_status(
+        event,
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339(registered).unwrap().into(),
+        )),
+    );
+
+    tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn periodic_time_is_wall_clock_in_event_timezone() {
+    // Helsinki is UTC+2 in winter and UTC+3 in summer
+    assert_eq!(
+        next_daily_in_helsinki("07:00", "2020-01-10T12:00:00+00:00").to_rfc3339(),
+        "2020-01-11T05:00:00+00:00"
+    );
+    assert_eq!(
+        next_daily_in_helsinki("07:00", "2020-07-10T12:00:00+00:00").to_rfc3339(),
+        "2020-07-11T04:00:00+00:00"
+    );
+}
+
+#[test]
+fn spring_transition_keeps_wall_clock_time() {
+    // Clocks move from 03:00 to 04:00 on 2020-03-29, registered on the day before
+    assert_eq!(
+        next_daily_in_helsinki("07:00", "2020-03-28T12:00:00+00:00").to_rfc3339(),
+        "2020-03-29T04:00:00+00:00"
+    );
+    // 03:30 does not exist, it's pushed forward to 04:30 summer time
+    assert_eq!(
+        next_daily_in_helsinki("03:30", "2020-03-28T12:00:00+00:00").to_rfc3339(),
+        "2020-03-29T01:30:00+00:00"
+    );
+}
+
+#[test]
+fn autumn_transition_keeps_wall_clock_time() {
+    // Clocks move from 04:00 back to 03:00 on 2020-10-25, registered on the day
+    // before
+    assert_eq!(
+        next_daily_in_helsinki("07:00", "2020-10-24T12:00:00+00:00").to_rfc3339(),
+        "2020-10-25T05:00:00+00:00"
+    );
+    // 03:30 occurs twice, the earlier one (summer time) is picked
+    assert_eq!(
+        next_daily_in_helsinki("03:30", "2020-10-24T12:00:00+00:00").to_rfc3339(),
+        "2020-10-25T00:30:00+00:00"
+    );
+}
+
+fn hm(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms(h, m, 0)
+}
+
+fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd(y, m, d)
+}
+
+#[test_case(TimePeriod::Daily(hm(15, 0)), "2020-01-31T16:00:00+00:00" => "2020-02-01T15:00:00+00:00" ; "daily month end")]
+#[test_case(TimePeriod::Daily(hm(15, 0)), "2020-12-31T16:00:00+00:00" => "2021-01-01T15:00:00+00:00" ; "daily year end")]
+#[test_case(TimePeriod::Daily(hm(15, 0)), "2020-02-28T16:00:00+00:00" => "2020-02-29T15:00:00+00:00" ; "daily leap day")]
+#[test_case(TimePeriod::Daily(hm(15, 0)), "2020-01-31T15:00:00+00:00" => "2020-02-01T15:00:00+00:00" ; "daily exactly at trigger time")]
+#[test_case(TimePeriod::Weekly(Weekday::Mon, hm(9, 0)), "2020-12-30T12:00:00+00:00" => "2021-01-04T09:00:00+00:00" ; "weekly across new year")]
+#[test_case(TimePeriod::Weekly(Weekday::Sun, hm(9, 0)), "2019-12-30T12:00:00+00:00" => "2020-01-05T09:00:00+00:00" ; "weekly in iso week of next year")]
+#[test_case(TimePeriod::Weekly(Weekday::Fri, hm(9, 0)), "2021-01-01T08:00:00+00:00" => "2021-01-01T09:00:00+00:00" ; "weekly in iso week of previous year")]
+#[test_case(TimePeriod::Weekly(Weekday::Wed, hm(9, 0)), "2020-01-29T10:00:00+00:00" => "2020-02-05T09:00:00+00:00" ; "weekly same weekday later")]
+#[test_case(TimePeriod::MultiDaily(vec![hm(8, 0), hm(14, 0), hm(20, 0)]), "2020-01-01T08:30:00+00:00" => "2020-01-01T14:00:00+00:00" ; "multi daily next slot")]
+#[test_case(TimePeriod::MultiDaily(vec![hm(8, 0), hm(14, 0), hm(20, 0)]), "2020-01-01T14:00:00+00:00" => "2020-01-01T20:00:00+00:00" ; "multi daily exactly at slot")]
+#[test_case(TimePeriod::SubDaily(DailyWindow { every_minutes: 120, from: hm(9, 0), until: hm(17, 0), weekdays: vec![] }), "2020-01-01T10:30:00+00:00" => "2020-01-01T11:00:00+00:00" ; "sub daily inside window")]
+#[test_case(TimePeriod::SubDaily(DailyWindow { every_minutes: 120, from: hm(9, 0), until: hm(17, 0), weekdays: vec![] }), "2020-01-01T17:00:00+00:00" => "2020-01-02T09:00:00+00:00" ; "sub daily after window")]
+#[test_case(TimePeriod::SubDaily(DailyWindow { every_minutes: 120, from: hm(9, 0), until: hm(17, 0), weekdays: vec![] }), "2020-01-01T15:00:00+00:00" => "2020-01-01T17:00:00+00:00" ; "sub daily window end is inclusive")]
+#[test_case(TimePeriod::SubDaily(DailyWindow { every_minutes: 120, from: hm(9, 0), until: hm(17, 0), weekdays: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] }), "2020-01-03T17:00:00+00:00" => "2020-01-06T09:00:00+00:00" ; "sub daily skips weekend")]
+#[test_case(TimePeriod::SubDaily(DailyWindow { every_minutes: 90, from: hm(22, 0), until: hm(2, 0), weekdays: vec![Weekday::Fri] }), "2020-01-04T00:00:00+00:00" => "2020-01-04T01:00:00+00:00" ; "sub daily window past midnight")]
+#[test_case(TimePeriod::MultiDaily(vec![hm(20, 0), hm(8, 0)]), "2020-12-31T21:00:00+00:00" => "2021-01-01T08:00:00+00:00" ; "multi daily across new year")]
+#[test_case(TimePeriod::MultiWeekly(WeeklyDays { weekdays: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri], every_n_weeks: 1, start: ymd(2020, 1, 1) }, hm(7, 0)), "2020-01-08T07:00:00+00:00" => "2020-01-10T07:00:00+00:00" ; "multi weekly next day in same week")]
+#[test_case(TimePeriod::MultiWeekly(WeeklyDays { weekdays: vec![Weekday::Mon, Weekday::Wed, Weekday::Fri], every_n_weeks: 1, start: ymd(2020, 1, 1) }, hm(7, 0)), "2020-01-10T07:00:00+00:00" => "2020-01-13T07:00:00+00:00" ; "multi weekly next week")]
+#[test_case(TimePeriod::MultiWeekly(WeeklyDays { weekdays: vec![Weekday::Tue], every_n_weeks: 2, start: ymd(2020, 12, 22) }, hm(9, 0)), "2020-12-22T10:00:00+00:00" => "2021-01-05T09:00:00+00:00" ; "biweekly across new year")]
+#[test_case(TimePeriod::MultiWeekly(WeeklyDays { weekdays: vec![Weekday::Mon, Weekday::Thu], every_n_weeks: 3, start: ymd(2020, 1, 8) }, hm(9, 0)), "2020-01-01T10:00:00+00:00" => "2020-01-09T09:00:00+00:00" ; "every three weeks not before start")]
+#[test_case(TimePeriod::MultiWeekly(WeeklyDays { weekdays: vec![Weekday::Mon, Weekday::Thu], every_n_weeks: 3, start: ymd(2020, 1, 8) }, hm(9, 0)), "2020-01-09T10:00:00+00:00" => "2020-01-27T09:00:00+00:00" ; "every three weeks skips inactive weeks")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 15, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-12-20T12:00:00+00:00" => "2021-01-15T09:00:00+00:00" ; "monthly december to january")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 15, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-12-15T09:00:00+00:00" => "2021-01-15T09:00:00+00:00" ; "monthly exactly at trigger time")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-04-01T12:00:00+00:00" => "2020-04-30T09:00:00+00:00" ; "monthly day 31 in 30 day month")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-02-01T12:00:00+00:00" => "2020-02-29T09:00:00+00:00" ; "monthly day 31 in leap february")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 30, overflow: DayOverflow::Clamp }, hm(9, 0)), "2021-01-31T12:00:00+00:00" => "2021-02-28T09:00:00+00:00" ; "monthly day 30 in february")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-04-30T12:00:00+00:00" => "2020-05-31T09:00:00+00:00" ; "monthly day 31 after clamped instance")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Roll }, hm(9, 0)), "2020-04-01T12:00:00+00:00" => "2020-05-01T09:00:00+00:00" ; "monthly day 31 rolls over")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Roll }, hm(9, 0)), "2020-05-01T12:00:00+00:00" => "2020-05-31T09:00:00+00:00" ; "monthly day 31 after roll over")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 30, overflow: DayOverflow::Roll }, hm(9, 0)), "2021-02-01T12:00:00+00:00" => "2021-03-01T09:00:00+00:00" ; "monthly day 30 rolls over february")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Roll }, hm(9, 0)), "2020-02-01T12:00:00+00:00" => "2020-03-01T09:00:00+00:00" ; "monthly day 31 rolls over leap february")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Skip }, hm(9, 0)), "2020-04-01T12:00:00+00:00" => "2020-05-31T09:00:00+00:00" ; "monthly day 31 skips 30 day month")]
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 31, overflow: DayOverflow::Skip }, hm(9, 0)), "2020-01-31T12:00:00+00:00" => "2020-03-31T09:00:00+00:00" ; "monthly day 31 skips february")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 2, day: 29, overflow: DayOverflow::Roll }, hm(9, 0)), "2020-03-01T12:00:00+00:00" => "2021-03-01T09:00:00+00:00" ; "annual leap day rolls over")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 2, day: 29, overflow: DayOverflow::Skip }, hm(9, 0)), "2020-03-01T12:00:00+00:00" => "2024-02-29T09:00:00+00:00" ; "annual leap day skips common years")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 2, day: 29, overflow: DayOverflow::Skip }, hm(9, 0)), "2096-03-01T12:00:00+00:00" => "2104-02-29T09:00:00+00:00" ; "annual leap day skips century")]
+#[test_case(TimePeriod::MonthlyWeekday(NthWeekday { nth: 1, weekday: Weekday::Mon }, hm(9, 0)), "2020-01-07T12:00:00+00:00" => "2020-02-03T09:00:00+00:00" ; "first monday of month")]
+#[test_case(TimePeriod::MonthlyWeekday(NthWeekday { nth: 1, weekday: Weekday::Mon }, hm(9, 0)), "2020-12-08T12:00:00+00:00" => "2021-01-04T09:00:00+00:00" ; "first monday across new year")]
+#[test_case(TimePeriod::MonthlyWeekday(NthWeekday { nth: -1, weekday: Weekday::Fri }, hm(16, 0)), "2020-02-01T12:00:00+00:00" => "2020-02-28T16:00:00+00:00" ; "last friday of month")]
+#[test_case(TimePeriod::MonthlyWeekday(NthWeekday { nth: -1, weekday: Weekday::Fri }, hm(16, 0)), "2020-07-31T16:00:00+00:00" => "2020-08-28T16:00:00+00:00" ; "last friday exactly at trigger time")]
+#[test_case(TimePeriod::MonthlyWeekday(NthWeekday { nth: 5, weekday: Weekday::Mon }, hm(9, 0)), "2020-01-01T12:00:00+00:00" => "2020-03-30T09:00:00+00:00" ; "fifth monday skips months without one")]
+#[test_case(TimePeriod::MonthEnd(hm(18, 0)), "2020-02-10T12:00:00+00:00" => "2020-02-29T18:00:00+00:00" ; "month end in leap february")]
+#[test_case(TimePeriod::MonthEnd(hm(18, 0)), "2020-12-31T19:00:00+00:00" => "2021-01-31T18:00:00+00:00" ; "month end across new year")]
+#[test_case(TimePeriod::MonthEnd(hm(18, 0)), "2020-04-30T12:00:00+00:00" => "2020-04-30T18:00:00+00:00" ; "month end on the last day")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 12, day: 31, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-12-31T12:00:00+00:00" => "2021-12-31T09:00:00+00:00" ; "annual year end")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 1, day: 1, overflow: DayOverflow::Clamp }, hm(0, 0)), "2020-12-31T23:59:00+00:00" => "2021-01-01T00:00:00+00:00" ; "annual new year")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 2, day: 29, overflow: DayOverflow::Clamp }, hm(9, 0)), "2020-03-01T12:00:00+00:00" => "2021-02-28T09:00:00+00:00" ; "annual leap day in common year")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 2, day: 29, overflow: DayOverflow::Clamp }, hm(9, 0)), "2023-03-01T12:00:00+00:00" => "2024-02-29T09:00:00+00:00" ; "annual leap day in leap year")]
+fn periodic_rollover(period: TimePeriod, registered: &str) -> String {
+    let mut tracker = Tracker::empty();
+    let event =
+        EventData::new(Interval::Periodic(period), "Periodic event".to_string()).with_timezone(UTC);
+    let handle = tracker.add_event_with_status(
+        event,
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339(registered).unwrap().into(),
+        )),
+    );
+
+    tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap()
+        .with_timezone(&Utc)
+        .to_rfc3339()
+}
+
+#[test_case(TimePeriod::Monthly(MonthlyDay { day: 0, overflow: DayOverflow::Clamp }, hm(9, 0)) => false ; "monthly day zero")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 13, day: 1, overflow: DayOverflow::Clamp }, hm(9, 0)) => false ; "annual month thirteen")]
+#[test_case(TimePeriod::Annual(AnnualDay { month: 0, day: 1, overflow: DayOverflow::Clamp }, hm(9, 0)) => false ; "annual month zero")]
+fn invalid_periodic_date_is_scheduled(period: TimePeriod) -> bool {
+    let mut tracker = Tracker::empty();
+    let handle = tracker.add_event(EventData::new(
+        Interval::Periodic(period),
+        "Invalid event".to_string(),
+    ));
+
+    tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .is_some()
+}
+
+#[test]
+fn completing_daily_slot_keeps_next_slot() {
+    let clock = ManualClock::new(instant("2020-01-01T07:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::MultiDaily(vec![hm(8, 0), hm(14, 0), hm(20, 0)])),
+            "Medication".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    // Complete the 08:00 slot
+    clock.set(instant("2020-01-01T08:30:00+00:00"));
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_triggered());
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_completed());
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS),
+        Some(instant("2020-01-01T14:00:00+00:00"))
+    );
+
+    // The 14:00 slot still triggers after the completion
+    clock.set(instant("2020-01-01T14:30:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.prev_trigger_time(),
+        Some(instant("2020-01-01T14:00:00+00:00"))
+    );
+}
+
+#[test]
+fn start_date_delays_first_trigger() {
+    let mut tracker = Tracker::empty();
+    let event = EventData::new(
+        Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+        "Physio".to_string(),
+    )
+    .with_timezone(UTC)
+    .with_start(ymd(2020, 3, 1));
+    let handle = tracker.add_event_with_status(
+        event,
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339("2020-01-01T12:00:00+00:00")
+                .unwrap()
+                .into(),
+        )),
+    );
+
+    let next = tracker
+        .event(handle)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .unwrap();
+    assert_eq!(
+        next.with_timezone(&Utc).to_rfc3339(),
+        "2020-03-01T09:00:00+00:00"
+    );
+}
+
+#[test]
+fn event_past_end_date_is_finished() {
+    let mut tracker = Tracker::empty();
+    let event = EventData::new(
+        Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+        "Physio".to_string(),
+    )
+    .with_timezone(UTC)
+    .with_end(ymd(2020, 1, 31));
+    let handle = tracker.add_event_with_status(
+        event,
+        Status::from_time(Time(
+            DateTime::parse_from_rfc3339("2020-01-31T12:00:00+00:00")
+                .unwrap()
+                .into(),
+        )),
+    );
+
+    // The next daily instance is on Feb. 1st, past the end date
+    let now = tracker.now();
+    let event = tracker.event_mut(handle).unwrap();
+    assert!(event.next_trigger_time(&NO_HOLIDAYS).is_none());
+    event.update_at(now, &NO_HOLIDAYS);
+    assert!(event.is_finished());
+    event.update_at(now, &NO_HOLIDAYS);
+    assert!(event.is_finished());
+}
+
+#[test]
+fn occurrence_limit_finishes_event() {
+    let mut tracker = Tracker::empty();
+    let event = EventData::new(
+        Interval::FromLastCompletion(TimeDelta::Hm(0, 1)),
+        "Twice".to_string(),
+    )
+    .with_max_occurrences(2);
+    let handle = tracker.add_event(event);
+    let now = tracker.now();
+    let event = tracker.event_mut(handle).unwrap();
+
+    for _ in 0..2 {
+        assert!(!event.is_finished());
+        assert!(event.next_trigger_time(&NO_HOLIDAYS).is_some());
+        event.trigger_at(now);
+        event.complete_at(now);
+        event.update_at(now, &NO_HOLIDAYS);
+    }
+
+    // Limit reached after completion, so the event is finished instead of triggering again
+    assert!(event.is_finished());
+    assert!(event.next_trigger_time(&NO_HOLIDAYS).is_none());
+    assert!(!event.is_triggered());
+}
+
+#[test]
+fn completing_finished_event_is_rejected() {
+    let mut tracker = Tracker::empty();
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::FromLastCompletion(TimeDelta::Hm(0, 1)),
+            "Once".to_string(),
+        )
+        .with_max_occurrences(1),
+    );
+    tracker
+        .apply_command(&command::TriggerCommand(uid, None))
+        .unwrap();
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_finished());
+    let history_len = tracker.event(uid).unwrap().1.history().len();
+
+    let result = tracker.apply_command(&command::CompleteCommand(vec![uid], None));
+    assert!(matches!(result, Err(CommandError::AlreadyFinished(_))));
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_finished());
+    assert_eq!(event.1.history().len(), history_len);
+}
+
+#[test]
+fn rrule_past_its_count_is_finished() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::RRule(
+                "DTSTART:20200101T090000\nRRULE:FREQ=DAILY;COUNT=2"
+                    .parse()
+                    .unwrap(),
+            ),
+            "Twice".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    for day in 1..=2 {
+        clock.set(instant(&format!("2020-01-{:02}T10:00:00+00:00", day)));
+        tracker.update_events();
+        assert!(tracker.event(uid).unwrap().is_triggered());
+        tracker
+            .apply_command(&command::CompleteCommand(vec![uid], None))
+            .unwrap();
+    }
+
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_finished());
+    assert!(event.next_trigger_time(&NO_HOLIDAYS).is_none());
+}
+
+#[test]
+fn only_new_occurrences_are_counted() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_max_occurrences(2),
+    );
+
+    // Triggering a triggered event again isn't a new occurrence
+    tracker
+        .apply_command(&command::TriggerCommand(uid, None))
+        .unwrap();
+    tracker
+        .apply_command(&command::TriggerCommand(uid, None))
+        .unwrap();
+    assert_eq!(tracker.event(uid).unwrap().1.occurrences(), 1);
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+
+    // Skipped occurrences don't count towards the limit
+    tracker
+        .apply_command(&command::SkipCommand(uid, 1))
+        .unwrap();
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(!event.is_triggered());
+    assert_eq!(event.1.occurrences(), 1);
+
+    clock.set(instant("2020-01-02T10:00:00+00:00"));
+    tracker.update_events();
+    assert_eq!(tracker.event(uid).unwrap().1.occurrences(), 2);
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_finished());
+
+    // Finished events stay finished when triggered by hand
+    tracker
+        .apply_command(&command::TriggerCommand(uid, None))
+        .unwrap();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_finished());
+    assert_eq!(event.1.occurrences(), 2);
+}
+
+#[test]
+fn skipped_one_shot_does_not_trigger() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Once(ymd(2020, 1, 1).and_time(hm(9, 0))),
+            "Dentist".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker
+        .apply_command(&command::SkipCommand(uid, 1))
+        .unwrap();
+
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_finished());
+    assert_eq!(event.1.occurrences(), 0);
+}
+
+#[test]
+fn one_shot_triggers_once() {
+    let mut cli = TrackerCli::new(Tracker::empty());
+    let event = EventData::new(
+        Interval::Once(ymd(2020, 3, 1).and_time(hm(9, 0))),
+        "Renew passport".to_string(),
+    )
+    .with_timezone(UTC);
+    let uid = cli.tracker.add_event(event);
+
+    // The time has passed already, so the event triggers on update
+    let now = cli.tracker.now();
+    let event = cli.tracker.event_mut(uid).unwrap();
+    assert_eq!(
+        event
+            .next_trigger_time(&NO_HOLIDAYS)
+            .unwrap()
+            .with_timezone(&Utc)
+            .to_rfc3339(),
+        "2020-03-01T09:00:00+00:00"
+    );
+    event.update_at(now, &NO_HOLIDAYS);
+    assert!(event.is_triggered());
+
+    cli.call("0");
+
+    let event = cli.tracker.event_mut(uid).unwrap();
+    assert!(event.is_finished());
+    event.update_at(now, &NO_HOLIDAYS);
+    assert!(!event.is_triggered());
+    assert!(event.next_trigger_time(&NO_HOLIDAYS).is_none());
+
+    // The completion resolves the triggered occurrence
+    assert_eq!(
+        event
+            .1
+            .history()
+            .iter()
+            .map(|e| e.action)
+            .collect::<Vec<HistoryAction>>(),
+        vec![HistoryAction::Trigger, HistoryAction::Complete]
+    );
+    let stats = cli.tracker.stats();
+    let one_shot_stats = &stats.events.iter().find(|(u, _)| *u == uid).unwrap().1;
+    assert_eq!(one_shot_stats.completed, 1);
+    assert_eq!(one_shot_stats.pending, 0);
+}
+
+#[test]
+fn one_shot_completed_early_is_finished() {
+    let mut cli = TrackerCli::new(Tracker::empty());
+    let event = EventData::new(
+        Interval::Once(ymd(2100, 1, 1).and_time(hm(9, 0))),
+        "Renew passport".to_string(),
+    );
+    let uid = cli.tracker.add_event(event);
+
+    cli.call("0");
+
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_finished());
+    assert!(event.next_trigger_time(&NO_HOLIDAYS).is_none());
+}
+
+#[test_case(chrono::Duration::days(3) + chrono::Duration::hours(4) + chrono::Duration::minutes(5) => "3d 4h" ; "days")]
+#[test_case(chrono::Duration::hours(2) + chrono::Duration::minutes(5) => "2h 5m" ; "hours")]
+#[test_case(chrono::Duration::seconds(30) => "1m" ; "under a minute")]
+#[test_case(chrono::Duration::minutes(-5) => "now" ; "passed")]
+fn countdown_formats_right(remaining: chrono::Duration) -> String {
+    crate::prelude::format_countdown(remaining)
+}
+
+fn instant(rfc3339: &str) -> chrono::DateTime<chrono::Local> {
+    DateTime::parse_from_rfc3339(rfc3339).unwrap().into()
+}
+
+#[test]
+fn simulate_two_weeks_of_usage() {
+    let clock = ManualClock::new(instant("2020-01-06T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+
+    let daily = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    let weekly = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Weekly(Weekday::Wed, hm(12, 0))),
+            "Weekly".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    let mut weekly_triggers = 0;
+    for _ in 0..14 {
+        // Before the daily trigger time nothing happens
+        tracker.update_events();
+        assert!(!tracker.event(daily).unwrap().is_triggered());
+
+        clock.advance(chrono::Duration::hours(1) + chrono::Duration::minutes(30));
+        tracker.update_events();
+        assert!(tracker.event(daily).unwrap().is_triggered());
+
+        // Complete the daily event as of the clock's time
+        tracker
+            .apply_command(&command::CompleteCommand(vec![daily], None))
+            .unwrap();
+        match &tracker.event(daily).unwrap().1.status {
+            StatusKind::Completed(t) => assert_eq!(t.0, clock.now()),
+            _ => unreachable!(),
+        }
+
+        clock.advance(chrono::Duration::hours(4));
+        tracker.update_events();
+        if tracker.event(weekly).unwrap().is_triggered() {
+            assert_eq!(clock.now().with_timezone(&Utc).weekday(), Weekday::Wed);
+            weekly_triggers += 1;
+            tracker
+                .apply_command(&command::CompleteCommand(vec![weekly], None))
+                .unwrap();
+        }
+
+        // Move to 08:00 of the next day
+        clock.advance(chrono::Duration::hours(18) + chrono::Duration::minutes(30));
+    }
+
+    assert_eq!(tracker.event(daily).unwrap().1.occurrences(), 14);
+    assert_eq!(weekly_triggers, 2);
+}
+
+#[test]
+fn loaded_events_are_updated_by_injected_clock() {
+    let path = temp_file_path("clock_test");
+    let clock = ManualClock::new(instant("2020-01-01T07:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker.store_to_disk(&path);
+
+    clock.set(instant("2020-01-01T08:00:00+00:00"));
+    let mut loaded = Tracker::from_path_with_clock(&path, clock.clone()).unwrap();
+    loaded.update_events();
+    assert!(!loaded.event(uid).unwrap().is_triggered());
+
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    loaded.refresh_from_disk(&path).unwrap();
+    let event = loaded.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.prev_trigger_time(),
+        Some(instant("2020-01-01T09:00:00+00:00"))
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn stacking_event_catches_up_on_missed_occurrences() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let handle = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Stacking".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+
+    // A week without updates
+    clock.set(instant("2020-01-08T10:00:00+00:00"));
+    tracker.update_events();
+
+    // Each of the occurrences is recorded at its scheduled time
+    let trigger_times = tracker
+        .event(handle)
+        .unwrap()
+        .1
+        .trigger_times()
+        .iter()
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+        .collect::<Vec<String>>();
+    let expected = (1..=8)
+        .map(|day| format!("2020-01-{:02}T09:00:00+00:00", day))
+        .collect::<Vec<String>>();
+    assert_eq!(trigger_times, expected);
+
+    // Nothing more to catch up on
+    tracker.update_events();
+    assert_eq!(tracker.event(handle).unwrap().1.trigger_times().len(), 8);
+}
+
+#[test]
+fn skipped_event_catches_up_after_skipped_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let handle = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Skipped".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker
+        .apply_command(&command::SkipCommand(handle, 1))
+        .unwrap();
+
+    // The skip cancels the first occurrence, the second one triggers
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    tracker.update_events();
+
+    let event = tracker.event(handle).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.trigger_times()[0].with_timezone(&Utc).to_rfc3339(),
+        "2020-01-02T09:00:00+00:00"
+    );
+}
+
+#[test]
+fn zero_delta_stacking_event_does_not_loop() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let handle = tracker.add_event(
+        EventData::new(
+            Interval::FromLastCompletion(TimeDelta::Hm(0, 0)),
+            "Zero".to_string(),
+        )
+        .with_stacks(true),
+    );
+
+    clock.advance(chrono::Duration::hours(1));
+    tracker.update_events();
+    assert!(tracker.event(handle).unwrap().is_triggered());
+}
+
+#[test]
+fn stacked_occurrences_complete_one_at_a_time() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Stacking".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    cli.tracker.update_events();
+    assert_eq!(cli.tracker.event(uid).unwrap().pending_occurrences(), 3);
+
+    // Completing removes the earliest occurrence
+    cli.call("0");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(event.pending_occurrences(), 2);
+    assert_eq!(
+        event.1.trigger_times()[0].with_timezone(&Utc).to_rfc3339(),
+        "2020-01-02T09:00:00+00:00"
+    );
+
+    cli.call("undo");
+    assert_eq!(cli.tracker.event(uid).unwrap().pending_occurrences(), 3);
+
+    // Completing all clears every occurrence at once
+    cli.call("all 0");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_completed());
+    assert_eq!(event.pending_occurrences(), 0);
+}
+
+#[test]
+fn last_stacked_occurrence_completes_event() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Stacking".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+    clock.set(instant("2020-01-02T10:00:00+00:00"));
+    cli.tracker.update_events();
+
+    cli.call("0");
+    assert!(cli.tracker.event(uid).unwrap().is_triggered());
+    cli.call("0");
+    assert!(cli.tracker.event(uid).unwrap().is_completed());
+}
+
+#[test]
+fn history_is_recorded_and_stored() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Filter".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    // Skip the first occurrence, trigger and complete the second one
+    cli.call("skip 0");
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    cli.tracker.update_events();
+    cli.call("0");
+    cli.call("history 0");
+
+    let expected = [
+        ("2020-01-01T09:00:00+00:00", HistoryAction::Skip),
+        ("2020-01-02T09:00:00+00:00", HistoryAction::Trigger),
+        ("2020-01-03T10:00:00+00:00", HistoryAction::Complete),
+    ];
+    let history = |status: &Status| {
+        status
+            .history()
+            .iter()
+            .map(|entry| (entry.time.with_timezone(&Utc).to_rfc3339(), entry.action))
+            .collect::<Vec<(String, HistoryAction)>>()
+    };
+    let status = &cli.tracker.event(uid).unwrap().1;
+    assert_eq!(
+        history(status),
+        expected
+            .iter()
+            .map(|(t, a)| (t.to_string(), *a))
+            .collect::<Vec<_>>()
+    );
+
+    // The history survives a round trip through the YAML store
+    let yaml = serde_yaml::to_string(status).unwrap();
+    let restored = serde_yaml::from_str::<Status>(&yaml).unwrap();
+    assert_eq!(history(&restored), history(status));
+}
+
+#[test]
+fn stats_track_rate_delay_and_streaks() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let daily = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    let other = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Weekly(Weekday::Sat, hm(20, 0))),
+            "Other".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    let complete = |tracker: &mut Tracker, uid: Uid| {
+        tracker
+            .apply_command(&command::CompleteCommand(vec![uid], None))
+            .unwrap();
+    };
+
+    // Completed 30 minutes and an hour late on the first two days
+    for (day, delay) in &[(1, 30), (2, 60)] {
+        clock.set(instant(&format!("2020-01-{:02}T09:00:00+00:00", day)));
+        tracker.update_events();
+        clock.advance(chrono::Duration::minutes(*delay));
+        complete(&mut tracker, daily);
+    }
+    let skip = |tracker: &mut Tracker, uid: Uid| {
+        tracker
+            .apply_command(&command::SkipCommand(uid, 1))
+            .unwrap();
+    };
+    // Skipped before it triggers on the third day
+    clock.set(instant("2020-01-03T08:00:00+00:00"));
+    skip(&mut tracker, daily);
+    // Completed 30 minutes late on the fourth day, the other event is skipped after that
+    clock.set(instant("2020-01-04T09:30:00+00:00"));
+    tracker.update_events();
+    complete(&mut tracker, daily);
+    clock.set(instant("2020-01-04T10:00:00+00:00"));
+    skip(&mut tracker, other);
+    // Pending on the fifth day
+    clock.set(instant("2020-01-05T09:30:00+00:00"));
+    tracker.update_events();
+
+    let stats = tracker.stats();
+    let daily_stats = &stats
+        .events
+        .iter()
+        .find(|(uid, _)| *uid == daily)
+        .unwrap()
+        .1;
+    assert_eq!(daily_stats.completed, 3);
+    assert_eq!(daily_stats.skipped, 1);
+    assert_eq!(daily_stats.pending, 1);
+    assert_eq!(daily_stats.completion_rate(), Some(0.75));
+    assert_eq!(
+        daily_stats.average_delay(),
+        Some(chrono::Duration::minutes(40))
+    );
+    assert_eq!(daily_stats.current_streak, 1);
+    assert_eq!(daily_stats.longest_streak, 2);
+
+    // The other event's skip breaks the overall streak
+    assert_eq!(stats.overall.completed, 3);
+    assert_eq!(stats.overall.skipped, 2);
+    assert_eq!(stats.overall.current_streak, 0);
+    assert_eq!(stats.overall.longest_streak, 2);
+}
+
+#[test]
+fn backdated_completion_schedules_from_given_time() {
+    let clock = ManualClock::new(instant("2020-01-02T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(EventData::new(
+        Interval::FromLastCompletion(TimeDelta::Days(2)),
+        "Water plants".to_string(),
+    ));
+    let yesterday = clock.now() - chrono::Duration::days(1);
+    cli.tracker.event_mut(uid).unwrap().trigger_at(yesterday);
+
+    let at = format!("{}", yesterday.format("%Y-%m-%dT18:30"));
+    cli.call(format!("done 0 at {}", at));
+
+    let event = cli.tracker.event(uid).unwrap();
+    let completed_at = match &event.1.status {
+        StatusKind::Completed(t) => t.0,
+        _ => unreachable!(),
+    };
+    assert_eq!(completed_at.format("%Y-%m-%dT%H:%M").to_string(), at);
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        completed_at + chrono::Duration::days(2)
+    );
+
+    // Undo restores the triggered state
+    cli.call("undo");
+    assert!(cli.tracker.event(uid).unwrap().is_triggered());
+
+    // Same with the short form
+    cli.call("0 @yesterday 18:30");
+    match &cli.tracker.event(uid).unwrap().1.status {
+        StatusKind::Completed(t) => assert_eq!(t.0, completed_at),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn backdated_trigger_and_future_times() {
+    let clock = ManualClock::new(instant("2020-01-02T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(EventData::new(
+        Interval::FromLastCompletion(TimeDelta::Days(2)),
+        "Water plants".to_string(),
+    ));
+
+    // Times in the future are rejected
+    cli.call("trig 0 @today 23:59");
+    assert!(!cli.tracker.event(uid).unwrap().is_triggered());
+
+    cli.call("trig 0 @yesterday 7:15");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        TimeSpec::from_str("yesterday 7:15")
+            .unwrap()
+            .resolve(&clock.now())
+    );
+
+    // Triggers before the latest one are rejected
+    cli.call("trig 0 @yesterday 7:00");
+    assert_eq!(cli.tracker.event(uid).unwrap().1.trigger_times().len(), 1);
+}
+
+#[test]
+fn completion_before_trigger_is_rejected() {
+    let clock = ManualClock::new(instant("2020-01-02T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    clock.set(instant("2020-01-02T10:00:00+00:00"));
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_triggered());
+
+    let result = tracker.apply_command(&command::CompleteCommand(
+        vec![uid],
+        Some(TimeSpec::from_str("2020-01-01T20:00").unwrap()),
+    ));
+    assert!(matches!(result, Err(CommandError::BeforeTrigger(_, _, _))));
+    assert!(tracker.event(uid).unwrap().is_triggered());
+
+    let result = tracker.apply_command(&command::TriggerCommand(
+        uid,
+        Some(TimeSpec::from_str("2020-01-01T20:00").unwrap()),
+    ));
+    assert!(matches!(result, Err(CommandError::BeforeTrigger(_, _, _))));
+    assert_eq!(tracker.event(uid).unwrap().1.history().len(), 1);
+}
+
+#[test_case("yesterday 18:30" => "2020-01-01 18:30" ; "yesterday")]
+#[test_case("9:05" => "2020-01-02 09:05" ; "time of day")]
+#[test_case("today" => "2020-01-02 12:00" ; "today")]
+#[test_case("2019-12-24T18:00" => "2019-12-24 18:00" ; "iso")]
+#[test_case("24.12.2019 18:00" => "2019-12-24 18:00" ; "date and time")]
+fn time_spec_resolves(spec: &str) -> String {
+    let now = Local.ymd(2020, 1, 2).and_hms(12, 0, 0);
+    spec.parse::<TimeSpec>()
+        .unwrap()
+        .resolve(&now)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+#[test]
+fn invalid_time_specs_are_rejected() {
+    for spec in &[
+        "",
+        "tomorrow",
+        "25:00",
+        "yesterday 18:30 extra",
+        "2019-13-01T10:00",
+    ] {
+        assert!(spec.parse::<TimeSpec>().is_err(), "'{}' was accepted", spec);
+    }
+}
+
+#[test]
+fn snoozed_event_triggers_again_at_deadline() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Snoozable".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    // Events that have not triggered cannot be snoozed
+    cli.call("snooze 0 2h");
+    assert!(!cli.tracker.event(uid).unwrap().is_snoozed());
+
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    cli.tracker.update_events();
+    cli.call("snooze 0 1h30m");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_snoozed());
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        instant("2020-01-01T11:00:00+00:00")
+    );
+
+    // Undo brings back the triggered state, redo the snooze
+    cli.call("undo");
+    assert!(cli.tracker.event(uid).unwrap().is_triggered());
+    cli.call("z 0 90m");
+
+    clock.set(instant("2020-01-01T10:59:00+00:00"));
+    cli.tracker.update_events();
+    assert!(cli.tracker.event(uid).unwrap().is_snoozed());
+
+    // At the deadline the same occurrence is triggered again
+    clock.set(instant("2020-01-01T11:00:00+00:00"));
+    cli.tracker.update_events();
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(event.1.trigger_times().len(), 1);
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        instant("2020-01-01T09:00:00+00:00")
+    );
+}
+
+#[test]
+fn completing_snoozed_event_completes_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Snoozable".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    cli.tracker.update_events();
+    cli.call("snooze 0 2h");
+
+    cli.call("0");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_completed());
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        instant("2020-01-02T09:00:00+00:00")
+    );
+}
+
+#[test]
+fn completing_untriggered_periodic_event_is_rejected() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    let result = tracker.apply_command(&command::CompleteCommand(vec![uid], None));
+    assert!(matches!(result, Err(CommandError::NotTriggered(_))));
+
+    // Nothing is skipped, the next occurrence triggers
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert!(event
+        .1
+        .history()
+        .iter()
+        .all(|e| e.action == HistoryAction::Trigger));
+}
+
+#[test]
+fn completing_current_occurrence_keeps_next_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    tracker.update_events();
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+
+    clock.set(instant("2020-01-02T09:30:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        instant("2020-01-02T09:00:00+00:00")
+    );
+}
+
+#[test]
+fn skipping_triggered_event_keeps_current_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    cli.tracker.update_events();
+
+    cli.call("skip 0");
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(event.1.skips(), 1);
+
+    // The current occurrence is completed, the next one is skipped and the one after triggers
+    cli.call("0");
+    clock.set(instant("2020-01-02T09:30:00+00:00"));
+    cli.tracker.update_events();
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(!event.is_triggered());
+    assert_eq!(event.1.skips(), 0);
+    assert_eq!(
+        event.1.history().last().unwrap().action,
+        HistoryAction::Skip
+    );
+
+    clock.set(instant("2020-01-03T09:30:00+00:00"));
+    cli.tracker.update_events();
+    assert!(cli.tracker.event(uid).unwrap().is_triggered());
+}
+
+#[test]
+fn skipping_triggered_stacking_event_skips_next_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    cli.tracker.update_events();
+
+    cli.call("skip 0");
+    clock.set(instant("2020-01-02T10:00:00+00:00"));
+    cli.tracker.update_events();
+    let event = cli.tracker.event(uid).unwrap();
+    assert_eq!(event.pending_occurrences(), 1);
+    assert_eq!(
+        event
+            .1
+            .history()
+            .iter()
+            .map(|e| e.action)
+            .collect::<Vec<HistoryAction>>(),
+        vec![HistoryAction::Trigger, HistoryAction::Skip]
+    );
+
+    // The occurrence after the skipped one stacks as usual
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    cli.tracker.update_events();
+    let event = cli.tracker.event(uid).unwrap();
+    assert_eq!(event.pending_occurrences(), 2);
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        instant("2020-01-03T09:00:00+00:00")
+    );
+}
+
+#[test]
+fn skipping_several_occurrences() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    cli.call("skip 0 3");
+    clock.set(instant("2020-01-04T10:00:00+00:00"));
+    cli.tracker.update_events();
+
+    let event = cli.tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        instant("2020-01-04T09:00:00+00:00")
+    );
+    let skipped = event
+        .1
+        .history()
+        .iter()
+        .filter(|e| e.action == HistoryAction::Skip)
+        .map(|e| e.time.with_timezone(&Utc).to_rfc3339())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        skipped,
+        vec![
+            "2020-01-01T09:00:00+00:00",
+            "2020-01-02T09:00:00+00:00",
+            "2020-01-03T09:00:00+00:00"
+        ]
+    );
+}
+
+#[test]
+fn skip_is_undone() {
+    let mut cli = TrackerCli::new(Tracker::empty());
+    let uid = cli.tracker.add_event(TEST_EVENT.clone());
+
+    cli.call("skip 0 2");
+    assert_eq!(cli.tracker.event(uid).unwrap().1.skips(), 2);
+    cli.call("undo");
+    assert_eq!(cli.tracker.event(uid).unwrap().1.skips(), 0);
+}
+
+#[test]
+fn legacy_skip_status_is_kept_pending() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    // Files from before skips were counted mark the skipped next occurrence with the status
+    let status = serde_yaml::from_str::<Status>(
+        "---
+trigger_times: []
+status:
+  Skip: \"2020-01-01T08:00:00+00:00\"
+history:
+  - time: \"2020-01-01T08:00:00+00:00\"
+    action: Skip
+",
+    )
+    .unwrap();
+    assert_eq!(status.skips(), 1);
+    assert!(status.history().is_empty());
+    let uid = tracker.add_event_with_status(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+        status,
+    );
+
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(!event.is_triggered());
+    assert_eq!(event.1.skips(), 0);
+    assert_eq!(event.1.history().len(), 1);
+
+    // Stored statuses keep their skips as they are
+    let yaml = serde_yaml::to_string(&event.1).unwrap();
+    assert_eq!(serde_yaml::from_str::<Status>(&yaml).unwrap().skips(), 0);
+
+    clock.set(instant("2020-01-02T10:00:00+00:00"));
+    tracker.update_events();
+    assert!(tracker.event(uid).unwrap().is_triggered());
+}
+
+#[test]
+fn trigger_after_skip_triggers() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker
+        .apply_command(&command::SkipCommand(uid, 1))
+        .unwrap();
+
+    // Triggering by hand doesn't use up the skip
+    tracker
+        .apply_command(&command::TriggerCommand(uid, None))
+        .unwrap();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_triggered());
+    assert_eq!(event.1.skips(), 1);
+    assert_eq!(
+        event.1.history().last().unwrap().action,
+        HistoryAction::Trigger
+    );
+
+    // The next scheduled occurrence is skipped instead
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+    clock.set(instant("2020-01-01T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(!event.is_triggered());
+    assert_eq!(event.1.skips(), 0);
+}
+
+#[test]
+fn early_completion_satisfies_upcoming_occurrence() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Water plants".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_early_window(TimeDelta::Hm(1, 0)),
+    );
+
+    // Outside the window there's nothing to complete
+    clock.set(instant("2020-01-01T07:30:00+00:00"));
+    assert!(tracker
+        .event(uid)
+        .unwrap()
+        .early_occurrence(&clock.now(), &NO_HOLIDAYS)
+        .is_none());
+    let result = tracker.apply_command(&command::CompleteCommand(vec![uid], None));
+    assert!(matches!(result, Err(CommandError::NotTriggered(_))));
+
+    // Within the window the upcoming occurrence is completed
+    clock.set(instant("2020-01-01T08:15:00+00:00"));
+    assert_eq!(
+        tracker
+            .event(uid)
+            .unwrap()
+            .early_occurrence(&clock.now(), &NO_HOLIDAYS),
+        Some(instant("2020-01-01T09:00:00+00:00"))
+    );
+    tracker
+        .apply_command(&command::CompleteCommand(vec![uid], None))
+        .unwrap();
+
+    // The occurrence does not trigger anymore, the next one does
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert!(event.is_completed());
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        instant("2020-01-02T09:00:00+00:00")
+    );
+
+    // Only the completion is recorded, with the occurrence it was for
+    let history = event.1.history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].action, HistoryAction::Complete);
+    assert_eq!(history[0].time.0, instant("2020-01-01T08:15:00+00:00"));
+    assert_eq!(
+        history[0].occurrence.as_ref().unwrap().0,
+        instant("2020-01-01T09:00:00+00:00")
+    );
+
+    // The completion counts as on time
+    let stats = &tracker.stats().events[0].1;
+    assert_eq!(stats.completed, 1);
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.average_delay(), Some(chrono::Duration::zero()));
+}
+
+#[test]
+fn early_completion_is_undone() {
+    let clock = ManualClock::new(instant("2020-01-01T08:30:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Water plants".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_early_window(TimeDelta::Hm(1, 0)),
+    );
+
+    cli.call("0");
+    assert!(cli.tracker.event(uid).unwrap().is_completed());
+    cli.call("undo");
+
+    clock.set(instant("2020-01-01T09:30:00+00:00"));
+    cli.tracker.update_events();
+    assert!(cli.tracker.event(uid).unwrap().is_triggered());
+}
+
+#[test]
+fn overdue_is_counted_from_first_trigger() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let graced = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Graced".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true)
+        .with_grace_period(TimeDelta::Hm(2, 0)),
+    );
+    let plain = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Plain".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+
+    // Within the grace period
+    clock.set(instant("2020-01-01T10:30:00+00:00"));
+    tracker.update_events();
+    assert_eq!(
+        tracker.event(graced).unwrap().overdue_by(&clock.now()),
+        None
+    );
+
+    // Later occurrences don't reset the overdue time
+    clock.set(instant("2020-01-02T10:30:00+00:00"));
+    tracker.update_events();
+    let now = clock.now();
+    assert_eq!(tracker.event(graced).unwrap().pending_occurrences(), 2);
+    assert_eq!(
+        tracker.event(graced).unwrap().overdue_by(&now),
+        Some(chrono::Duration::hours(23) + chrono::Duration::minutes(30))
+    );
+    // Without a grace period the event is overdue after the length of its interval
+    assert_eq!(
+        tracker.event(plain).unwrap().overdue_by(&now),
+        Some(chrono::Duration::minutes(90))
+    );
+
+    // Completed events are not overdue
+    tracker
+        .apply_command(&command::CompleteAllCommand(vec![graced], None))
+        .unwrap();
+    assert_eq!(tracker.event(graced).unwrap().overdue_by(&now), None);
+}
+
+#[test]
+fn overdue_events_are_listed_first() {
+    let clock = ManualClock::new(instant("2020-01-01T05:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let daily = |time, text: &str| {
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(time)),
+            text.to_string(),
+        )
+        .with_timezone(UTC)
+    };
+    tracker.add_event(
+        EventData::new(
+            Interval::Once(ymd(2020, 1, 5).and_time(hm(9, 0))),
+            "Upcoming later".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker.add_event(daily(hm(9, 0), "Overdue").with_grace_period(TimeDelta::Hm(2, 0)));
+    tracker.add_event(daily(hm(20, 0), "Triggered"));
+    tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Weekly(Weekday::Thu, hm(12, 0))),
+            "Upcoming".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    tracker.add_event(daily(hm(6, 0), "Most overdue").with_grace_period(TimeDelta::Hm(1, 0)));
+
+    clock.set(instant("2020-01-01T21:00:00+00:00"));
+    tracker.update_events();
+    let now = clock.now();
+    let mut events = tracker
+        .events()
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect::<Vec<&TrackedEvent>>();
+    events.sort_by(|te1, te2| {
+        crate::view::tracker_cli::sort_by_next_trigger(te1, te2, &now, &NO_HOLIDAYS)
+    });
+
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.text())
+            .collect::<Vec<&str>>(),
+        vec![
+            "Most overdue",
+            "Overdue",
+            "Triggered",
+            "Upcoming",
+            "Upcoming later"
+        ]
+    );
+}
+
+#[test]
+fn paused_event_resumes_from_end_of_pause() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let daily = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+    let timer = cli.tracker.add_event(EventData::new(
+        Interval::FromLastCompletion(TimeDelta::Days(2)),
+        "Timer".to_string(),
+    ));
+
+    cli.tracker
+        .apply_command(&command::PauseCommand(daily))
+        .unwrap();
+    cli.tracker
+        .apply_command(&command::PauseCommand(timer))
+        .unwrap();
+    assert!(cli.tracker.event(daily).unwrap().is_paused());
+    assert!(cli
+        .tracker
+        .event(daily)
+        .unwrap()
+        .next_trigger_time(&NO_HOLIDAYS)
+        .is_none());
+
+    // Nothing triggers during the pause
+    clock.set(instant("2020-01-05T12:00:00+00:00"));
+    cli.tracker.update_events();
+    assert!(!cli.tracker.event(daily).unwrap().is_triggered());
+    assert!(!cli.tracker.event(timer).unwrap().is_triggered());
+
+    // Resuming doesn't fire the missed occurrences, the schedule continues from now on
+    cli.tracker
+        .apply_command(&command::ResumeCommand(daily))
+        .unwrap();
+    cli.tracker
+        .apply_command(&command::ResumeCommand(timer))
+        .unwrap();
+    cli.tracker.update_events();
+    let event = cli.tracker.event(daily).unwrap();
+    assert!(!event.is_paused());
+    assert!(!event.is_triggered());
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        instant("2020-01-06T09:00:00+00:00")
+    );
+    assert_eq!(
+        cli.tracker
+            .event(timer)
+            .unwrap()
+            .next_trigger_time(&NO_HOLIDAYS)
+            .unwrap(),
+        instant("2020-01-07T12:00:00+00:00")
+    );
+
+    // Undoing the resume pauses the event again
+    cli.call("undo");
+    assert!(cli.tracker.event(timer).unwrap().is_paused());
+    cli.call("undo");
+    assert!(cli.tracker.event(daily).unwrap().is_paused());
+}
+
+#[test]
+fn pausing_twice_is_rejected() {
+    let mut tracker = Tracker::empty();
+    let uid = tracker.add_event(TEST_EVENT.clone());
+
+    tracker.apply_command(&command::PauseCommand(uid)).unwrap();
+    assert!(matches!(
+        tracker.apply_command(&command::PauseCommand(uid)),
+        Err(CommandError::AlreadyPaused(_))
+    ));
+    tracker.apply_command(&command::ResumeCommand(uid)).unwrap();
+    assert!(matches!(
+        tracker.apply_command(&command::ResumeCommand(uid)),
+        Err(CommandError::NotPaused(_))
+    ));
+}
+
+#[test]
+fn nothing_triggers_during_vacation() {
+    let clock = ManualClock::new(instant("2020-01-01T08:00:00+00:00"));
+    let mut cli = TrackerCli::new(Tracker::empty().with_clock(clock.clone()));
+    let uid = cli.tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Daily".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true),
+    );
+    let one_shot = cli.tracker.add_event(
+        EventData::new(
+            Interval::Once(ymd(2020, 1, 3).and_time(hm(9, 0))),
+            "Dentist".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    cli.call("vacation 2020-01-02 2020-01-04");
+    assert_eq!(cli.tracker.vacations().len(), 1);
+
+    // The one-shot event is put off until the end of the vacation
+    clock.set(instant("2020-01-03T10:00:00+00:00"));
+    cli.tracker.update_events();
+    assert!(!cli.tracker.event(one_shot).unwrap().is_triggered());
+
+    clock.set(instant("2020-01-05T10:00:00+00:00"));
+    cli.tracker.update_events();
+    assert_eq!(
+        cli.tracker
+            .event(one_shot)
+            .unwrap()
+            .1
+            .prev_trigger_time()
+            .unwrap(),
+        instant("2020-01-05T00:00:00+00:00")
+    );
+
+    // The occurrences before and after the vacation trigger
+    let trigger_times = cli
+        .tracker
+        .event(uid)
+        .unwrap()
+        .1
+        .trigger_times()
+        .iter()
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        trigger_times,
+        vec!["2020-01-01T09:00:00+00:00", "2020-01-05T09:00:00+00:00"]
+    );
+
+    cli.call("undo");
+    assert!(cli.tracker.vacations().is_empty());
+}
+
+#[test]
+fn vacation_cannot_end_before_it_starts() {
+    let mut tracker = Tracker::empty();
+    let result = tracker.apply_command(&command::VacationCommand(
+        TimeSpec::from_str("2020-01-04").unwrap(),
+        TimeSpec::from_str("2020-01-02").unwrap(),
+    ));
+    assert!(matches!(result, Err(CommandError::EndsBeforeStart(_, _))));
+    assert!(tracker.vacations().is_empty());
+}
+
+#[test]
+fn vacations_are_stored_and_legacy_files_load() {
+    let path = temp_file_path("vacation_test");
+    let mut tracker = Tracker::empty();
+    tracker.add_event(TEST_EVENT.clone());
+    tracker.add_vacation(Vacation::new(ymd(2020, 7, 1), ymd(2020, 7, 14)).unwrap());
+    tracker.store_to_disk(&path);
+
+    let restored = Tracker::from_path(&path).unwrap();
+    assert_eq!(restored.vacations(), tracker.vacations());
+    assert_eq!(restored.events().len(), 1);
+
+    // Files from before vacations were stored contain just the events
+    std::fs::write(
+        &path,
+        "---
+0:
+  - text: Legacy event
+    interval:
+      FromLastCompletion:
+        Days: 1
+    stacks: false
+  - trigger_times: []
+    status:
+      Dormant: \"2100-01-01T00:00:00+00:00\"
+",
+    )
+    .unwrap();
+    let legacy = Tracker::from_path(&path).unwrap();
+    assert_eq!(legacy.events().len(), 1);
+    assert!(legacy.vacations().is_empty());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn seasonal_event_triggers_only_in_season() {
+    let clock = ManualClock::new(instant("2020-09-29T08:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let uid = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Daily(hm(9, 0))),
+            "Water the garden".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_stacks(true)
+        .with_seasons(vec![Season::from_str("5.1.-9.30.").unwrap()]),
+    );
+
+    // The last two days of the season trigger, then nothing until May
+    clock.set(instant("2020-12-01T10:00:00+00:00"));
+    tracker.update_events();
+    let event = tracker.event(uid).unwrap();
+    assert_eq!(event.pending_occurrences(), 2);
+    assert_eq!(
+        event.1.prev_trigger_time().unwrap(),
+        instant("2020-09-30T09:00:00+00:00")
+    );
+    assert_eq!(
+        event.next_trigger_time(&NO_HOLIDAYS).unwrap(),
+        instant("2021-05-01T09:00:00+00:00")
+    );
+}
+
+#[test]
+fn event_registered_out_of_season_waits_for_next_season() {
+    let clock = ManualClock::new(instant("2021-03-15T12:00:00+00:00"));
+    let mut tracker = Tracker::empty().with_clock(clock.clone());
+    let winter = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::Weekly(Weekday::Mon, hm(7, 0))),
+            "De-ice the stairs".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_seasons(vec![Season::from_str("12.1.-2.29.").unwrap()]),
+    );
+    let timer = tracker.add_event(
+        EventData::new(
+            Interval::FromLastCompletion(TimeDelta::Days(2)),
+            "Mow the lawn".to_string(),
+        )
+        .with_timezone(UTC)
+        .with_seasons(vec![Season::from_str("5.1.-9.30.").unwrap()]),
+    );
+
+    // The first Monday of December
+    assert_eq!(
+        tracker
+            .event(winter)
+            .unwrap()
+            .next_trigger_time(&NO_HOLIDAYS)
+            .unwrap(),
+        instant("2021-12-06T07:00:00+00:00")
+    );
+    // Timers start at the beginning of the season
+    assert_eq!(
+        tracker
+            .event(timer)
+            .unwrap()
+            .next_trigger_time(&NO_HOLIDAYS)
+            .unwrap(),
+        instant("2021-05-01T00:00:00+00:00")
+    );
+}
+
+#[test]
+fn business_day_schedules_skip_weekends_and_holidays() {
+    // Registered on Wednesday the 23rd after the trigger time
+    let clock = ManualClock::new(instant("2020-12-23T10:00:00+00:00"));
+    let mut tracker =
+        Tracker::empty()
+            .with_clock(clock.clone())
+            .with_holidays(HolidayCalendar::new(vec![
+                ymd(2020, 12, 24),
+                ymd(2020, 12, 25),
+            ]));
+    let daily = tracker.add_event(
+        EventData::new(
+            Interval::Periodic(TimePeriod::BusinessDaily(hm(9, 0))),
+            "Check the mail".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    assert_eq!(
+        tracker
+            .event(daily)
+            .unwrap()
+            .next_trigger_time(tracker.holidays())
+            .unwrap(),
+        instant("2020-12-28T09:00:00+00:00")
+    );
+    // Timers count business days after the completion
+    let timer = tracker.add_event(
+        EventData::new(
+            Interval::FromLastCompletion(TimeDelta::BusinessDays(3)),
+            "Follow up".to_string(),
+        )
+        .with_timezone(UTC),
+    );
+    assert_eq!(
+        tracker
+            .event(timer)
+            .unwrap()
+            .next_trigger_time(tracker.holidays())
+            .unwrap(),
+        instant("2020-12-30T10:00:00+00:00")
+    );
+
+    // Updating the events skips the holidays as well
+    clock.set(instant("2020-12-24T10:00:00+00:00"));
+    tracker.update_events();
+    assert!(!tracker.event(daily).unwrap().is_triggered());
+    clock.set(instant("2020-12-28T10:00:00+00:00"));
+    tracker.update_events();
+    assert_eq!(
+        tracker.event(daily).unwrap().1.prev_trigger_time(),
+        Some(instant("2020-12-28T09:00:00+00:00"))
+    );
+}
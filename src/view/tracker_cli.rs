@@ -31,7 +31,7 @@ impl TrackerCli {
     {
         debug!("Out-of-order 'call' starts");
 
-        let now = Local::now();
+        let now = self.tracker.now();
         let visible_events = self.generate_events_list(&now);
         let cmd = self.interpret(input.as_ref(), &visible_events);
 
@@ -56,7 +56,7 @@ impl TrackerCli {
             println!();
 
             // Set up cached variables
-            let now = Local::now();
+            let now = self.tracker.now();
 
             trace!("Refreshing tracked events");
             self.tracker.update_events();
@@ -123,6 +123,7 @@ impl TrackerCli {
                 .iter()
                 .map(|(uid, _)| *uid)
                 .collect::<Vec<tracker::Uid>>(),
+            &self.tracker.now(),
         );
 
         if let Some(ref cmd) = cmd {
@@ -172,11 +173,12 @@ impl TrackerCli {
     }
 
     fn generate_events_list(&self, now: &DateTime<Local>) -> Vec<(tracker::Uid, &TrackedEvent)> {
+        let holidays = self.tracker.holidays();
         let mut events = self.tracker.events();
         match self.state {
             // Extended mode: show all events, sorted by next trigger time
             ViewState::Extended => {
-                events.sort_by(|(_, te1), (_, te2)| sort_by_next_trigger(te1, te2));
+                events.sort_by(|(_, te1), (_, te2)| sort_by_next_trigger(te1, te2, now, holidays));
             }
             // Standard mode: show triggered events + lookahead, sorted by next trigger time
             ViewState::Standard => {
@@ -185,13 +187,26 @@ impl TrackerCli {
                     .filter(|&(_, event)| {
                         if event.1.is_triggered() {
                             true
+                        } else if event.is_paused() {
+                            // Paused events are listed so that they can be resumed
+                            true
+                        } else if event.early_occurrence(now, holidays).is_some() {
+                            // Offer events for completion within their early window
+                            true
+                        } else if event.1.is_snoozed() {
+                            // Snoozed events are out of sight until they trigger again
+                            false
+                        } else if !event.0.interval().is_recurring() {
+                            // Show pending one-shot events with a countdown until they trigger
+                            event.next_trigger_time(holidays).is_some()
                         } else {
                             // Show other entries if their next trigger is within look-ahead scope
-                            matches!(event.fraction_of_interval_remaining(now), Some(remaining) if remaining < LOOK_AHEAD_FRAC)
+                            matches!(event.fraction_of_interval_remaining(now, holidays), Some(remaining) if remaining < LOOK_AHEAD_FRAC)
                         }
                     }).copied()
                     .collect::<Vec<(tracker::Uid, &TrackedEvent)>>();
-                filtered_events.sort_by(|(_, te1), (_, te2)| sort_by_next_trigger(te1, te2));
+                filtered_events
+                    .sort_by(|(_, te1), (_, te2)| sort_by_next_trigger(te1, te2, now, holidays));
                 events = filtered_events;
             }
         }
@@ -199,7 +214,7 @@ impl TrackerCli {
     }
 
     fn visualize(&self, visible_events: &[(Uid, &TrackedEvent)]) {
-        let now = Local::now();
+        let now = self.tracker.now();
 
         let state_str = match self.state {
             ViewState::Standard => "standard",
@@ -210,37 +225,50 @@ impl TrackerCli {
         if !visible_events.is_empty() {
             let visible_events: Vec<(usize, &(Uid, &TrackedEvent))> =
                 visible_events.as_ref().iter().enumerate().collect();
-            let split_idx = visible_events.iter().position(|(_idx, (_uid, event))| {
-                match event.0.interval().to_duration_heuristic() {
-                    Some(duration) => duration >= Duration::days(1) + Duration::hours(1),
-                    None => false,
-                }
-            });
-
-            let (daily_events, other_events) = match split_idx {
-                None => (visible_events.as_slice(), None),
-                Some(idx) => {
-                    let (a, b) = visible_events.split_at(idx);
-                    (a, Some(b))
-                }
+            // Events that trigger at least daily, including sub-daily ones, are grouped
+            // together regardless of their position in the list
+            let (daily_events, other_events): (Vec<_>, Vec<_>) = visible_events
+                .into_iter()
+                .partition(|(_idx, (_uid, event))| {
+                    match event.0.interval().to_duration_heuristic() {
+                        Some(duration) => duration < Duration::days(1) + Duration::hours(1),
+                        None => false,
+                    }
+                });
+            let other_events = match other_events.is_empty() {
+                true => None,
+                false => Some(other_events),
             };
 
             // Print status
             println!("=== Daily Events ({})) ===", state_str);
             for (idx, (_, event)) in daily_events {
-                self.print_event_line(*idx, event, &now);
+                self.print_event_line(idx, event, &now);
             }
             if let Some(events) = other_events {
                 println!();
                 println!("=== Events ({})) ===", state_str);
                 for (idx, (_, event)) in events {
-                    self.print_event_line(*idx, event, &now);
+                    self.print_event_line(idx, event, &now);
                 }
             }
         } else {
             println!("=== No Events ({})) ===", state_str);
         }
 
+        // Print vacations that haven't ended yet
+        let vacations = self
+            .tracker
+            .vacations()
+            .iter()
+            .filter(|vacation| vacation.ends() >= now.date().naive_local())
+            .map(|vacation| vacation.to_string())
+            .collect::<Vec<String>>();
+        if !vacations.is_empty() {
+            println!();
+            println!("Vacations: {}", vacations.join(", "));
+        }
+
         // Print commands
         println!();
         println!("=== Commands ===");
@@ -276,42 +304,138 @@ impl TrackerCli {
         prev
     }
 
+    pub fn print_history(&self, event: &TrackedEvent) {
+        println!("=== History of \"{}\" ===", event.text());
+        let history = event.1.history();
+        if history.is_empty() {
+            println!("Nothing has happened yet");
+        }
+        for entry in history {
+            let occurrence = match &entry.occurrence {
+                Some(t) => format!(" ahead of {}", t.format("%a %-d.%-m.%Y %H:%M")),
+                None => String::new(),
+            };
+            println!(
+                "{:<10} {:<5}  {}{}",
+                entry.time.format("%a %-d.%-m.%Y"),
+                entry.time.format("%H:%M"),
+                entry.action,
+                occurrence
+            );
+        }
+    }
+
+    pub fn print_stats(&self) {
+        let stats = self.tracker.stats();
+
+        println!("=== Statistics ===");
+        println!(
+            "{:<24} {:>5} {:>5} {:>10} {:>7} {:>7} {:>7}",
+            "Event", "Done", "Rate", "Avg delay", "Streak", "Longest", "Skipped"
+        );
+        let print_row = |name: &str, stats: &EventStats| {
+            println!(
+                "{:<24} {:>5} {:>5} {:>10} {:>7} {:>7} {:>7}",
+                name,
+                stats.completed,
+                match stats.completion_rate() {
+                    Some(rate) => format!("{:.0}%", rate * 100.),
+                    None => "-".to_string(),
+                },
+                match stats.average_delay() {
+                    Some(delay) if delay < Duration::minutes(1) => "0m".to_string(),
+                    Some(delay) => format_countdown(delay),
+                    None => "-".to_string(),
+                },
+                stats.current_streak,
+                stats.longest_streak,
+                stats.skipped
+            );
+        };
+        for (uid, event_stats) in &stats.events {
+            if let Some(event) = self.tracker.event(*uid) {
+                let name = event.text().chars().take(24).collect::<String>();
+                print_row(&name, event_stats);
+            }
+        }
+        print_row("Overall", &stats.overall);
+    }
+
     fn print_event_line(&self, idx: usize, event: &TrackedEvent, now: &LocalTime) {
+        let holidays = self.tracker.holidays();
         match self.state {
             ViewState::Standard => match event.1.status {
                 // Show triggered entries
                 StatusKind::Triggered => {
-                    println!("* ({id:>2})   {text}", id = idx, text = event.text());
+                    println!(
+                        "* ({id:>2})   {text}{pending}{overdue}{paused}",
+                        id = idx,
+                        text = event.text(),
+                        pending = pending_str(event),
+                        overdue = overdue_str(event, now),
+                        paused = if event.is_paused() { " (paused)" } else { "" }
+                    );
+                }
+                // Show paused entries
+                _ if event.is_paused() => {
+                    println!(
+                        "  ({id:>2})   ({text}) - (paused)",
+                        id = idx,
+                        text = event.text(),
+                    );
+                }
+                // Show upcoming occurrences that may be completed ahead of time
+                _ if event.early_occurrence(now, holidays).is_some() => {
+                    println!(
+                        "  ({id:>2})   {text} - (triggers {time}, can be completed now)",
+                        id = idx,
+                        text = event.text(),
+                        time = trigger_time_str(&event.next_trigger_time(holidays).unwrap(), now),
+                    );
                 }
-                // Show non-triggered if close to triggering, HACK: unless they're "Skip"
+                // Show pending one-shot events with the time remaining
+                _ if !event.0.interval().is_recurring() => {
+                    if let Some(t) = event.next_trigger_time(holidays) {
+                        println!(
+                            "  ({id:>2})   ({text}) - (due in {countdown})",
+                            id = idx,
+                            text = event.text(),
+                            countdown = format_countdown(t.signed_duration_since(*now)),
+                        );
+                    }
+                }
+                // Show non-triggered if close to triggering, noting if the occurrence is skipped
                 _ => {
-                    if event.fraction_of_interval_remaining(now).is_some() {
-                        if let StatusKind::Skip(_) = event.1.status {
-                        } else {
-                            println!(
-                                "  ({id:>2})   ({text}) - (triggers {time})",
-                                id = idx,
-                                text = event.text(),
-                                time = {
-                                    let t = event.next_trigger_time().unwrap();
-                                    if is_today(&t) {
-                                        t.format("today at %H:%M")
-                                    } else {
-                                        t.format("on %d.%m. at %H:%M")
-                                    }
-                                }
-                            );
-                        }
+                    if event
+                        .fraction_of_interval_remaining(now, holidays)
+                        .is_some()
+                    {
+                        println!(
+                            "  ({id:>2})   ({text}) - ({verb} {time})",
+                            id = idx,
+                            text = event.text(),
+                            verb = if event.1.skips() > 0 {
+                                "skips"
+                            } else {
+                                "triggers"
+                            },
+                            time =
+                                trigger_time_str(&event.next_trigger_time(holidays).unwrap(), now),
+                        );
                     }
                 }
             },
             ViewState::Extended => {
                 println!(
-                    "{trig} ({id:>2}) {next} - {text} ({interval}, current: {state:?})",
+                    "{trig} ({id:>2}) {next} - {text}{pending}{overdue} ({interval}, current: {state:?})",
                     id = idx,
                     text = event.text(),
+                    pending = pending_str(event),
+                    overdue = overdue_str(event, now),
                     interval = event.0.interval(),
-                    next = match &event.next_trigger_time() {
+                    next = match &event.next_trigger_time(holidays) {
+                        None if event.is_finished() => format!("{:>16}", "Finished"),
+                        None if event.is_paused() => format!("{:>16}", "Paused"),
                         None => format!("{:>16}", "Not scheduled"),
                         Some(time) => format!(
                             "{:<10} {:<5}",
@@ -322,6 +446,7 @@ impl TrackerCli {
                     state = &event.1,
                     trig = match event.1.status {
                         StatusKind::Triggered => "*",
+                        StatusKind::Snoozed(_) => "z",
                         _ => " ",
                     }
                 );
@@ -342,7 +467,7 @@ enum ControlAction {
     Exit,
 }
 
-pub fn create_event_interact() -> Option<command::CreateCommand> {
+pub fn create_event_interact(now: &LocalTime) -> Option<command::CreateCommand> {
     // What?
     let text = dialoguer::Input::<String>::new()
         .with_prompt("What? (type text)")
@@ -358,9 +483,17 @@ pub fn create_event_interact() -> Option<command::CreateCommand> {
     let choices = &[
         "A constant time after the last completion of the event",
         "Daily",
+        "Several times a day at a fixed interval",
         "Weekly",
         "Monthly",
+        "Monthly on a weekday, eg. the first Monday",
+        "Monthly on the last day",
         "Annually",
+        "Annually on multiple dates",
+        "By an iCalendar recurrence rule (RRULE)",
+        "By a cron expression",
+        "Once, at a given date and time",
+        "Every business day, skipping weekends and holidays",
     ];
 
     let selection = dialoguer::Select::with_theme(&theme::ColorfulTheme::default())
@@ -379,37 +512,73 @@ pub fn create_event_interact() -> Option<command::CreateCommand> {
         },
         // Daily
         1 => {
-            let time = match input_time("At what time?") {
-                Some(t) => t,
-                None => {
+            let mut times = vec![];
+            while let Some(time) = input_time(match times.len() {
+                0 => "At what time?",
+                _ => "At what other time? (leave empty to finish)",
+            }) {
+                times.push(time);
+            }
+            times.sort();
+            times.dedup();
+
+            match times.len() {
+                0 => {
                     println!("Aborting 'add event'");
                     return None;
                 }
-            };
-            Interval::Periodic(TimePeriod::Daily(time))
+                1 => Interval::Periodic(TimePeriod::Daily(times[0])),
+                _ => Interval::Periodic(TimePeriod::MultiDaily(times)),
+            }
         }
-        // Weekly
+        // Several times a day
         2 => {
-            let weekday = match crate::view::troubleshoot::choices(
-                "Which day of the week? (number)",
-                &[
-                    "Monday",
-                    "Tuesday",
-                    "Wednesday",
-                    "Thursday",
-                    "Friday",
-                    "Saturday",
-                    "Sunday",
-                ],
+            let every = match input_time("How often? eg. 2:15 for every 2 hours 15 minutes") {
+                Some(t) if t.hour() * 60 + t.minute() > 0 => t.hour() * 60 + t.minute(),
+                _ => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+            let (from, until) = match (
+                input_time("Starting from what time of day?"),
+                input_time("Until what time of day?"),
             ) {
-                0 => Weekday::Mon,
-                1 => Weekday::Tue,
-                2 => Weekday::Wed,
-                3 => Weekday::Thu,
-                4 => Weekday::Fri,
-                5 => Weekday::Sat,
-                6 => Weekday::Sun,
-                _ => unreachable!(),
+                (Some(from), Some(until)) => (from, until),
+                _ => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+            let weekdays = input_weekdays("On which days of the week? (leave empty for every day)");
+
+            Interval::Periodic(TimePeriod::SubDaily(DailyWindow {
+                every_minutes: every,
+                from,
+                until,
+                weekdays,
+            }))
+        }
+        // Weekly
+        3 => {
+            let weekdays = input_weekdays("Which days of the week? (space to select)");
+            if weekdays.is_empty() {
+                println!("No days selected, aborting 'add event'");
+                return None;
+            }
+            let every_n_weeks =
+                match input::<u32>("Every how many weeks? (number, leave empty for 1)") {
+                    None => 1,
+                    Some(0) => {
+                        println!("Aborting 'add event'");
+                        return None;
+                    }
+                    Some(n) => n,
+                };
+            let start = match every_n_weeks {
+                1 => now.date().naive_local(),
+                _ => input_date("Starting from which date? (leave empty for this week)")
+                    .unwrap_or_else(|| now.date().naive_local()),
             };
             let time = match input_time("At what time?") {
                 Some(t) => t,
@@ -419,11 +588,23 @@ pub fn create_event_interact() -> Option<command::CreateCommand> {
                 }
             };
 
-            Interval::Periodic(TimePeriod::Weekly(weekday, time))
+            // A single day on every week is a plain weekly event
+            if weekdays.len() == 1 && every_n_weeks == 1 {
+                Interval::Periodic(TimePeriod::Weekly(weekdays[0], time))
+            } else {
+                Interval::Periodic(TimePeriod::MultiWeekly(
+                    WeeklyDays {
+                        weekdays,
+                        every_n_weeks,
+                        start,
+                    },
+                    time,
+                ))
+            }
         }
         // Monthly
-        3 => {
-            let day = match input("Which day? (number)") {
+        4 => {
+            let day = match input_in_range("Which day? (number)", 1, 31) {
                 Some(d) => d,
                 None => {
                     println!("Aborting 'add event'");
@@ -438,18 +619,61 @@ pub fn create_event_interact() -> Option<command::CreateCommand> {
                 }
             };
 
-            Interval::Periodic(TimePeriod::Monthly(MonthlyDay { day }, time))
+            let mut day = MonthlyDay {
+                day,
+                overflow: DayOverflow::default(),
+            };
+            if day.can_overflow() {
+                day.overflow = input_day_overflow();
+            }
+
+            Interval::Periodic(TimePeriod::Monthly(day, time))
+        }
+        // Monthly on a weekday
+        5 => {
+            let nth = match crate::view::troubleshoot::choices(
+                "Which one of the weekdays in the month?",
+                &["First", "Second", "Third", "Fourth", "Fifth", "Last"],
+            ) {
+                5 => -1,
+                n => n as i32 + 1,
+            };
+            let weekday = input_weekday("Which day of the week?");
+            let time = match input_time("At what time?") {
+                Some(t) => t,
+                None => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+
+            Interval::Periodic(TimePeriod::MonthlyWeekday(
+                NthWeekday { nth, weekday },
+                time,
+            ))
+        }
+        // Monthly on the last day
+        6 => {
+            let time = match input_time("At what time?") {
+                Some(t) => t,
+                None => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+
+            Interval::Periodic(TimePeriod::MonthEnd(time))
         }
         // Annually
-        4 => {
-            let month = match input("Which month? (number)") {
+        7 => {
+            let month = match input_in_range("Which month? (number)", 1, 12) {
                 Some(m) => m,
                 None => {
                     println!("Aborting 'add event'");
                     return None;
                 }
             };
-            let day = match input("Which day? (number)") {
+            let day = match input_in_range("Which day? (number)", 1, 31) {
                 Some(m) => m,
                 None => {
                     println!("Aborting 'add event'");
@@ -464,12 +688,232 @@ pub fn create_event_interact() -> Option<command::CreateCommand> {
                 }
             };
 
-            Interval::Periodic(TimePeriod::Annual(AnnualDay { month, day }, time))
+            let mut day = AnnualDay {
+                month,
+                day,
+                overflow: DayOverflow::default(),
+            };
+            if day.can_overflow() {
+                day.overflow = input_day_overflow();
+            }
+
+            Interval::Periodic(TimePeriod::Annual(day, time))
+        }
+        // Annually on multiple dates
+        8 => {
+            let mut days = vec![];
+            while let Some(month) =
+                input_in_range("Which month? (number, leave empty to finish)", 1, 12)
+            {
+                let day = match input_in_range("Which day? (number)", 1, 31) {
+                    Some(d) => d,
+                    None => {
+                        println!("Aborting 'add event'");
+                        return None;
+                    }
+                };
+                let time = match input_time("At what time?") {
+                    Some(t) => t,
+                    None => {
+                        println!("Aborting 'add event'");
+                        return None;
+                    }
+                };
+                let mut day = AnnualDay {
+                    month,
+                    day,
+                    overflow: DayOverflow::default(),
+                };
+                if day.can_overflow() {
+                    day.overflow = input_day_overflow();
+                }
+                days.push((day, time));
+            }
+            if days.is_empty() {
+                println!("No dates given, aborting 'add event'");
+                return None;
+            }
+
+            Interval::Periodic(TimePeriod::MultiAnnual(days))
         }
+        // Recurrence rule
+        9 => match input_rule("Input a recurrence rule, eg. FREQ=MONTHLY;BYDAY=-1FR;BYHOUR=9") {
+            None => {
+                println!("Aborting 'add event'");
+                return None;
+            }
+            Some(rule) => Interval::RRule(rule),
+        },
+        // Cron expression
+        10 => match input_rule("Input a cron expression, eg. 30 7 * * MON-FRI") {
+            None => {
+                println!("Aborting 'add event'");
+                return None;
+            }
+            Some(cron) => Interval::Cron(cron),
+        },
+        // Once
+        11 => {
+            let date = match input_date("On which date?") {
+                Some(d) => d,
+                None => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+            let time = match input_time("At what time?") {
+                Some(t) => t,
+                None => {
+                    println!("Aborting 'add event'");
+                    return None;
+                }
+            };
+            Interval::Once(date.and_time(time))
+        }
+        // Every business day
+        12 => match input_time("At what time?") {
+            Some(time) => Interval::Periodic(TimePeriod::BusinessDaily(time)),
+            None => {
+                println!("Aborting 'add event'");
+                return None;
+            }
+        },
         _ => unreachable!(),
     };
 
-    Some(command::CreateCommand(EventData::new(interval, text)))
+    // Periodic events may be bound to a time zone other than the local one
+    let timezone = match interval {
+        Interval::FromLastCompletion(_) => None,
+        _ => input_rule::<chrono_tz::Tz>(
+            "In which time zone? (IANA name, eg. Europe/Helsinki, leave empty for local time)",
+        ),
+    };
+
+    let mut event = EventData::new(interval, text);
+    if let Some(tz) = timezone {
+        event = event.with_timezone(tz);
+    }
+
+    // Recurring events may stack up occurrences that haven't been completed
+    if event.interval().is_recurring() {
+        let stacks = dialoguer::Confirmation::new()
+            .with_text("Keep each occurrence pending until completed instead of overriding the previous one?")
+            .default(false)
+            .interact()
+            .unwrap();
+        event = event.with_stacks(stacks);
+    }
+
+    // Triggered events that stay pending for long enough become overdue
+    if let Some(grace) = input_rule::<TimeDelta>(
+        "How long may it stay triggered before it is overdue? (eg. 2h, leave empty for the length of the interval)",
+    ) {
+        event = event.with_grace_period(grace);
+    }
+
+    // Scheduled occurrences may be completed a while before they trigger
+    if let Interval::Periodic(_) | Interval::RRule(_) | Interval::Cron(_) = event.interval() {
+        if let Some(window) = input_rule::<TimeDelta>(
+            "How long before triggering may it be completed? (eg. 1h, leave empty to disallow)",
+        ) {
+            event = event.with_early_window(window);
+        }
+    }
+
+    // Optionally limit the event to a date range or a number of occurrences
+    let limit = dialoguer::Confirmation::new()
+        .with_text("Limit the event to a date range, a number of times or seasons?")
+        .default(false)
+        .interact()
+        .unwrap();
+    if limit {
+        if let Some(starts) = input_date("Starting from which date? (leave empty to start now)") {
+            event = event.with_start(starts);
+        }
+        if let Some(ends) = input_date("Until which date? (leave empty for no end date)") {
+            event = event.with_end(ends);
+        }
+        if let Some(n) = input::<u32>("How many times at most? (leave empty for no limit)") {
+            event = event.with_max_occurrences(n);
+        }
+        let mut seasons = vec![];
+        while let Some(season) = input_rule::<Season>(
+            "Active during which part of the year? (month.day., eg. 5.1.-9.30., leave empty when done)",
+        ) {
+            seasons.push(season);
+        }
+        event = event.with_seasons(seasons);
+    }
+
+    Some(command::CreateCommand(event))
+}
+
+pub fn input_weekday(prompt: &str) -> Weekday {
+    match crate::view::troubleshoot::choices(
+        prompt,
+        &[
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+    ) {
+        0 => Weekday::Mon,
+        1 => Weekday::Tue,
+        2 => Weekday::Wed,
+        3 => Weekday::Thu,
+        4 => Weekday::Fri,
+        5 => Weekday::Sat,
+        6 => Weekday::Sun,
+        _ => unreachable!(),
+    }
+}
+
+pub fn input_weekdays(prompt: &str) -> Vec<Weekday> {
+    let weekdays = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+    dialoguer::Checkboxes::with_theme(&theme::ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(&[
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ])
+        .interact()
+        .unwrap()
+        .into_iter()
+        .map(|idx| weekdays[idx])
+        .collect()
+}
+
+pub fn input_day_overflow() -> DayOverflow {
+    match crate::view::troubleshoot::choices(
+        "The day is missing from some months or years, what to do then?",
+        &[
+            "Trigger on the last day of the month",
+            "Trigger on the next day",
+            "Skip the month or the year",
+        ],
+    ) {
+        0 => DayOverflow::Clamp,
+        1 => DayOverflow::Roll,
+        2 => DayOverflow::Skip,
+        _ => unreachable!(),
+    }
 }
 
 pub fn create_timedelta() -> Option<TimeDelta> {
@@ -478,6 +922,8 @@ pub fn create_timedelta() -> Option<TimeDelta> {
         "Trigger every N days",
         // "Hm { hours: i32, minutes: i32 }"
         "Trigger every h:mm hours and minutes",
+        // "BusinessDays(u32)"
+        "Trigger every N business days, skipping weekends and holidays",
     ];
 
     let selection = dialoguer::Select::with_theme(&theme::ColorfulTheme::default())
@@ -497,6 +943,10 @@ pub fn create_timedelta() -> Option<TimeDelta> {
                 TimeDelta::Hm(t.hour().try_into().unwrap(), t.minute().try_into().unwrap())
             })
         }
+        2 => {
+            let days = input("Input a number of business days for the interval");
+            days.map(TimeDelta::BusinessDays)
+        }
         _ => unreachable!(),
     }
 }
@@ -526,6 +976,51 @@ where
     }
 }
 
+/// Prompts for a number between `min` and `max`, asking again until one is
+/// given. Returns None if the input is left empty.
+pub fn input_in_range(prompt: &str, min: u32, max: u32) -> Option<u32> {
+    let input = dialoguer::Input::<String>::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(move |input: &str| -> Result<(), String> {
+            match input.parse::<u32>() {
+                _ if input.is_empty() => Ok(()),
+                Ok(n) if n >= min && n <= max => Ok(()),
+                _ => Err(format!("Input a number from {} to {}", min, max)),
+            }
+        })
+        .interact()
+        .expect("unable to parse string from user input");
+    input.parse().ok()
+}
+
+/// Prompts for a schedule expression, such as a recurrence rule or a cron
+/// expression, until it parses or the input is empty
+pub fn input_rule<T>(prompt: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    loop {
+        let input = dialoguer::Input::<String>::new()
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .interact()
+            .expect("unable to parse string from user input");
+        if input.is_empty() {
+            return None;
+        }
+
+        match input.parse() {
+            Ok(rule) => return Some(rule),
+            Err(e) => {
+                println!("{}\n", e);
+                continue;
+            }
+        }
+    }
+}
+
 const TIME_FORMATS: &[&str] = &[
     "%H:%M:%S",
     "%H.%M.%S",
@@ -557,8 +1052,75 @@ pub fn input_time(prompt: &str) -> Option<chrono::NaiveTime> {
     }
 }
 
-fn sort_by_next_trigger(te1: &TrackedEvent, te2: &TrackedEvent) -> Ordering {
-    match (te1.next_trigger_time(), te2.next_trigger_time()) {
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d.%m.%Y", "%d.%m.%y"];
+
+pub fn input_date(prompt: &str) -> Option<chrono::NaiveDate> {
+    loop {
+        let input = dialoguer::Input::<String>::new()
+            .with_prompt(prompt)
+            .allow_empty(true)
+            .interact()
+            .expect("unable to parse string from user input");
+        if input.is_empty() {
+            return None;
+        }
+
+        // Parse date from input
+        for fmt in DATE_FORMATS {
+            if let Ok(date) = chrono::NaiveDate::parse_from_str(&input, fmt) {
+                return Some(date);
+            }
+        }
+        println!("Cannot parse date from {}\n", input);
+        continue;
+    }
+}
+
+/// Returns the trigger time for display relative to the current day
+fn trigger_time_str(t: &LocalTime, now: &LocalTime) -> String {
+    if is_today(t, now) {
+        t.format("today at %H:%M").to_string()
+    } else {
+        t.format("on %d.%m. at %H:%M").to_string()
+    }
+}
+
+/// Returns the count of pending occurrences for display if there's more than
+/// one
+fn pending_str(event: &TrackedEvent) -> String {
+    match event.pending_occurrences() {
+        0 | 1 => String::new(),
+        n => format!(" ({} pending)", n),
+    }
+}
+
+/// Returns how long the event has been overdue for display, if it is
+fn overdue_str(event: &TrackedEvent, now: &LocalTime) -> String {
+    match event.overdue_by(now) {
+        Some(overdue) => format!(" (overdue {})", format_countdown(overdue)),
+        None => String::new(),
+    }
+}
+
+/// Orders overdue events first, then triggered events and then the rest by
+/// their next trigger time
+pub fn sort_by_next_trigger(
+    te1: &TrackedEvent,
+    te2: &TrackedEvent,
+    now: &LocalTime,
+    holidays: &HolidayCalendar,
+) -> Ordering {
+    // Overdue events go first, the most overdue one at the top
+    match (te1.overdue_by(now), te2.overdue_by(now)) {
+        (Some(o1), Some(o2)) => return o2.cmp(&o1),
+        (Some(_), None) => return Ordering::Less,
+        (None, Some(_)) => return Ordering::Greater,
+        (None, None) => {}
+    }
+    match (
+        te1.next_trigger_time(holidays),
+        te2.next_trigger_time(holidays),
+    ) {
         // Both are going to trigger in the future: later trigger == greater (goes later in list)
         (Some(t1), Some(t2)) => t1.cmp(&t2),
         // First one has a time when it's going to trigger, the other one is probably triggered